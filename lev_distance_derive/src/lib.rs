@@ -0,0 +1,112 @@
+//! Derive macros for the [`lev_distance`](https://docs.rs/lev_distance) crate.
+//!
+//! Use them through the `derive` feature of `lev_distance` rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident};
+
+/// Implements `lev_distance::FuzzyFromStr` for an enum of unit variants.
+///
+/// Variant names are matched exactly; anything else is rejected with a
+/// `FuzzyParseError` carrying the closest variant name, if there is one.
+#[proc_macro_derive(FuzzyFromStr)]
+pub fn derive_fuzzy_from_str(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_fuzzy_from_str(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_fuzzy_from_str(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`FuzzyFromStr` can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut idents = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`FuzzyFromStr` only supports unit variants",
+            ));
+        }
+        idents.push(&variant.ident);
+    }
+    let names: Vec<_> = idents.iter().map(|ident| unraw(ident)).collect();
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lev_distance::FuzzyFromStr for #name #ty_generics #where_clause {
+            const VARIANTS: &'static [&'static str] = &[#(#names),*];
+
+            fn from_str_fuzzy(s: &str) -> ::core::result::Result<Self, ::lev_distance::FuzzyParseError> {
+                match s {
+                    #(#names => ::core::result::Result::Ok(Self::#idents),)*
+                    _ => ::core::result::Result::Err(
+                        ::lev_distance::FuzzyParseError::new(s, Self::VARIANTS),
+                    ),
+                }
+            }
+        }
+    })
+}
+
+/// Implements `lev_distance::FieldNames` for a struct with named fields.
+#[proc_macro_derive(FieldNames)]
+pub fn derive_field_names(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_field_names(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_field_names(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`FieldNames` requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "`FieldNames` can only be derived for structs",
+            ))
+        }
+    };
+
+    let names = fields
+        .named
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(unraw));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lev_distance::FieldNames for #name #ty_generics #where_clause {
+            const FIELD_NAMES: &'static [&'static str] = &[#(#names),*];
+        }
+    })
+}
+
+/// The name of `ident` as written in source, without any `r#` prefix.
+fn unraw(ident: &Ident) -> String {
+    let ident = ident.to_string();
+    ident
+        .strip_prefix("r#")
+        .map(str::to_string)
+        .unwrap_or(ident)
+}
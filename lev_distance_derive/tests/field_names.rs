@@ -0,0 +1,27 @@
+use lev_distance::FieldNames;
+
+#[allow(dead_code)]
+#[derive(FieldNames)]
+struct Config {
+    max_retries: u32,
+    timeout_ms: u64,
+    r#type: String,
+}
+
+#[test]
+fn test_field_names() {
+    assert_eq!(Config::FIELD_NAMES, &["max_retries", "timeout_ms", "type"]);
+}
+
+#[test]
+fn test_suggest_field() {
+    assert_eq!(
+        Config::suggest_field("max_retry"),
+        Some("max_retries".to_string())
+    );
+    assert_eq!(
+        Config::suggest_field("ms_timeout"),
+        Some("timeout_ms".to_string())
+    );
+    assert_eq!(Config::suggest_field("verbose"), None);
+}
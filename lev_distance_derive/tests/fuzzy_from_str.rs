@@ -0,0 +1,46 @@
+use lev_distance::FuzzyFromStr;
+
+#[derive(Debug, PartialEq, FuzzyFromStr)]
+enum Level {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, FuzzyFromStr)]
+enum Keyword {
+    r#match,
+    r#loop,
+}
+
+#[test]
+fn test_exact_variant() {
+    assert_eq!(Level::from_str_fuzzy("Verbose"), Ok(Level::Verbose));
+    assert_eq!(Level::VARIANTS, &["Quiet", "Normal", "Verbose"]);
+}
+
+#[test]
+fn test_raw_variant() {
+    assert_eq!(Keyword::VARIANTS, &["match", "loop"]);
+    assert_eq!(Keyword::from_str_fuzzy("match"), Ok(Keyword::r#match));
+    assert_eq!(
+        Keyword::from_str_fuzzy("lop").unwrap_err().suggestion(),
+        Some("loop")
+    );
+}
+
+#[test]
+fn test_suggested_variant() {
+    let err = Level::from_str_fuzzy("Verbos").unwrap_err();
+    assert_eq!(err.input(), "Verbos");
+    assert_eq!(err.suggestion(), Some("Verbose"));
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `Verbos`, did you mean `Verbose`?"
+    );
+
+    let err = Level::from_str_fuzzy("xyz").unwrap_err();
+    assert_eq!(err.suggestion(), None);
+    assert_eq!(err.to_string(), "unknown variant `xyz`");
+}
@@ -0,0 +1,160 @@
+//! Abbreviations common in code, such as `cfg` for `config`.
+
+use crate::{find_best_match_for_name, CaseFolding, WordSplit};
+
+/// Common abbreviations in identifiers and the words they stand for.
+pub const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("addr", "address"),
+    ("app", "application"),
+    ("arg", "argument"),
+    ("args", "arguments"),
+    ("arr", "array"),
+    ("attr", "attribute"),
+    ("auth", "authentication"),
+    ("buf", "buffer"),
+    ("calc", "calculate"),
+    ("cfg", "config"),
+    ("cmd", "command"),
+    ("conf", "config"),
+    ("conn", "connection"),
+    ("ctx", "context"),
+    ("cur", "current"),
+    ("db", "database"),
+    ("del", "delete"),
+    ("desc", "description"),
+    ("dir", "directory"),
+    ("doc", "document"),
+    ("dst", "destination"),
+    ("elem", "element"),
+    ("env", "environment"),
+    ("err", "error"),
+    ("fn", "function"),
+    ("func", "function"),
+    ("idx", "index"),
+    ("impl", "implementation"),
+    ("info", "information"),
+    ("init", "initialize"),
+    ("len", "length"),
+    ("lib", "library"),
+    ("max", "maximum"),
+    ("mgr", "manager"),
+    ("min", "minimum"),
+    ("msg", "message"),
+    ("num", "number"),
+    ("obj", "object"),
+    ("opt", "option"),
+    ("param", "parameter"),
+    ("pkg", "package"),
+    ("pos", "position"),
+    ("prev", "previous"),
+    ("ptr", "pointer"),
+    ("ref", "reference"),
+    ("repo", "repository"),
+    ("req", "request"),
+    ("res", "response"),
+    ("spec", "specification"),
+    ("src", "source"),
+    ("str", "string"),
+    ("sync", "synchronize"),
+    ("tmp", "temporary"),
+    ("util", "utility"),
+    ("val", "value"),
+    ("var", "variable"),
+];
+
+/// Whether `abbr` abbreviates `word`, ignoring case.
+///
+/// Either the pair is in [`ABBREVIATIONS`], or `abbr` is shorter than
+/// `word`, starts with the same char and is a subsequence of it, like `mgmt`
+/// for `management`.
+pub fn is_abbreviation(abbr: &str, word: &str) -> bool {
+    let eq = |a: &str, b: &str| CaseFolding::Unicode.eq(a, b);
+    if ABBREVIATIONS
+        .iter()
+        .any(|&(short, long)| eq(short, abbr) && eq(long, word))
+    {
+        return true;
+    }
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let mut abbr_chars = abbr.chars().map(fold);
+    let mut word_chars = word.chars().map(fold);
+    match (abbr_chars.next(), word_chars.next()) {
+        (Some(a), Some(w)) if a == w => {}
+        _ => return false,
+    }
+    abbr.chars().count() < word.chars().count() && abbr_chars.all(|a| word_chars.any(|w| w == a))
+}
+
+/// Whether every word of `lookup` equals or abbreviates the corresponding
+/// word of `candidate`, with words split as by [`WordSplit::all`], so that
+/// `cfgPath` abbreviates `config_path`.
+pub fn abbreviates(lookup: &str, candidate: &str) -> bool {
+    let split = WordSplit::all();
+    split.words(lookup).count() == split.words(candidate).count()
+        && split
+            .words(lookup)
+            .zip(split.words(candidate))
+            .all(|(a, w)| CaseFolding::Unicode.eq(a, w) || is_abbreviation(a, w))
+}
+
+/// Like [`find_best_match_for_name`], falling back to the shortest candidate
+/// that `lookup` [`abbreviates`] if no tier matches, so that `ctx_len`
+/// suggests `context_length`.
+pub fn find_best_match_for_name_with_abbreviations<T>(
+    iter_names: impl Iterator<Item = T> + Clone,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    find_best_match_for_name(iter_names.clone(), lookup, dist).or_else(|| {
+        iter_names
+            .filter(|candidate| abbreviates(lookup, candidate.as_ref()))
+            .min_by_key(|candidate| candidate.as_ref().len())
+            .map(|candidate| candidate.as_ref().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_abbreviation() {
+        for (abbr, word) in [
+            ("cfg", "config"),
+            ("len", "length"),
+            ("idx", "index"),
+            ("Mgmt", "management"),
+        ] {
+            assert!(is_abbreviation(abbr, word), "{:?} {:?}", abbr, word);
+        }
+        for (abbr, word) in [
+            ("fig", "config"),
+            ("index", "index"),
+            ("", "a"),
+            ("xdi", "index"),
+        ] {
+            assert!(!is_abbreviation(abbr, word), "{:?} {:?}", abbr, word);
+        }
+        assert!(abbreviates("cfgPath", "config_path"));
+        assert!(!abbreviates("cfg", "config_path"));
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_with_abbreviations() {
+        let input = ["context_length", "config_path", "cache_len"];
+        for (lookup, expected) in [
+            ("ctx_len", Some("context_length")),
+            ("cfg_path", Some("config_path")),
+            ("cache_len", Some("cache_len")),
+            ("unrelated", None),
+        ] {
+            assert_eq!(
+                find_best_match_for_name_with_abbreviations(input.iter(), lookup, None),
+                expected.map(String::from)
+            );
+        }
+    }
+}
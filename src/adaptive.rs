@@ -0,0 +1,85 @@
+//! Thresholds derived from the candidate set.
+//!
+//! The default threshold only looks at the lookup. In a dense candidate set,
+//! where names differ by one or two edits, it happily suggests a name that is
+//! barely closer than its neighbours. Limiting the threshold to half of the
+//! smallest distance between two candidates keeps every accepted lookup
+//! unambiguously closer to its suggestion than to anything else.
+
+use std::cmp;
+
+use crate::{find_best_match_for_name, kernel, Threshold};
+
+/// Derives the maximum allowable edit distance for `lookup` from the
+/// candidates.
+///
+/// This is the usual one-third of the lookup's length, capped at half of the
+/// minimum distance between any two candidates (but never below one).
+/// Computing it is quadratic in the number of candidates.
+pub fn adaptive_max_dist<T>(candidates: impl Iterator<Item = T>, lookup: &str) -> usize
+where
+    T: AsRef<str>,
+{
+    let default = Threshold::OneThird.max_dist(lookup);
+    let candidates: Vec<T> = candidates.collect();
+
+    // Separations of `2 * default` or more don't lower the threshold, so the
+    // kernels may give up early beyond that.
+    let limit = 2 * default;
+    let mut min_separation = limit;
+    for (i, a) in candidates.iter().enumerate() {
+        for b in &candidates[i + 1..] {
+            let dist = kernel::distance(a.as_ref(), b.as_ref(), Some(limit));
+            min_separation = cmp::min(min_separation, dist);
+        }
+    }
+    cmp::min(default, cmp::max(min_separation / 2, 1))
+}
+
+/// Like [`find_best_match_for_name`], with the maximum distance chosen by
+/// [`adaptive_max_dist`].
+pub fn find_best_match_for_name_adaptive<T>(
+    iter_names: impl Iterator<Item = T> + Clone,
+    lookup: &str,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    let max_dist = adaptive_max_dist(iter_names.clone(), lookup);
+    find_best_match_for_name(iter_names, lookup, Some(max_dist))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_max_dist() {
+        // Well separated candidates keep the default threshold.
+        let input = ["apple", "orange", "banana"];
+        assert_eq!(adaptive_max_dist(input.iter(), "bananna"), 2);
+
+        // Candidates two edits apart only allow a single edit.
+        let input = ["read_file", "load_file"];
+        assert_eq!(adaptive_max_dist(input.iter(), "rexd_fxle"), 1);
+
+        assert_eq!(adaptive_max_dist(["a", "b"].iter(), "c"), 1);
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_adaptive() {
+        let input = ["read_file", "load_file"];
+        assert_eq!(
+            find_best_match_for_name(input.iter(), "rexd_fxle", None),
+            Some("read_file".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name_adaptive(input.iter(), "rexd_fxle"),
+            None
+        );
+        assert_eq!(
+            find_best_match_for_name_adaptive(input.iter(), "read_fil"),
+            Some("read_file".to_string())
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! User-supplied synonyms for words no string metric relates.
+
+use std::collections::HashMap;
+
+use crate::{CaseFolding, Pipeline, QueryContext};
+
+/// A table of words that mean the same, such as `delete` and `remove`.
+///
+/// Aliasing is an equivalence: aliases of aliases are aliases, and words
+/// compare ignoring case.
+#[derive(Clone, Debug, Default)]
+pub struct Aliases {
+    /// The group of every folded word.
+    groups: HashMap<String, usize>,
+    next_group: usize,
+}
+
+impl Aliases {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Aliases::default()
+    }
+
+    /// Makes `a` and `b` aliases of each other.
+    pub fn alias(mut self, a: &str, b: &str) -> Self {
+        let (a, b) = (fold(a), fold(b));
+        match (self.groups.get(&a).copied(), self.groups.get(&b).copied()) {
+            (Some(x), Some(y)) if x != y => {
+                for group in self.groups.values_mut() {
+                    if *group == y {
+                        *group = x;
+                    }
+                }
+            }
+            (Some(_), Some(_)) => {}
+            (Some(x), None) => {
+                self.groups.insert(b, x);
+            }
+            (None, Some(y)) => {
+                self.groups.insert(a, y);
+            }
+            (None, None) => {
+                self.groups.insert(a, self.next_group);
+                self.groups.insert(b, self.next_group);
+                self.next_group += 1;
+            }
+        }
+        self
+    }
+
+    /// Whether `a` and `b` are aliases of each other.
+    pub fn are_aliases(&self, a: &str, b: &str) -> bool {
+        match (self.groups.get(&fold(a)), self.groups.get(&fold(b))) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        }
+    }
+
+    /// Like [`find_best_match_for_name`](crate::find_best_match_for_name),
+    /// with aliases of `lookup` at distance 0: they lose only to a candidate
+    /// equal to `lookup` up to case, and beat every other candidate.
+    pub fn find_best_match_for_name<T>(
+        &self,
+        iter_names: impl Iterator<Item = T>,
+        lookup: &str,
+        dist: Option<usize>,
+    ) -> Option<String>
+    where
+        T: AsRef<str>,
+    {
+        let pipeline = Pipeline {
+            threshold: dist.into(),
+            case_folding: CaseFolding::Unicode,
+            ..Pipeline::default()
+        };
+        // The first alias is picked up on the way through the pipeline.
+        let mut alias = None;
+        let candidates = iter_names.inspect(|candidate| {
+            if alias.is_none() && self.are_aliases(candidate.as_ref(), lookup) {
+                alias = Some(candidate.as_ref().to_string());
+            }
+        });
+        let best = pipeline
+            .find(candidates, lookup, &mut QueryContext::default())
+            .map(|(candidate, _)| candidate.as_ref().to_string());
+        match best {
+            Some(best) if CaseFolding::Unicode.eq(&best, lookup) => Some(best),
+            best => alias.or(best),
+        }
+    }
+}
+
+fn fold(s: &str) -> String {
+    CaseFolding::Unicode.folded(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases() {
+        let aliases = Aliases::new()
+            .alias("delete", "remove")
+            .alias("colour", "color")
+            .alias("rm", "remove");
+        assert!(aliases.are_aliases("Delete", "RM"));
+        assert!(!aliases.are_aliases("delete", "color"));
+        assert!(!aliases.are_aliases("delete", "unknown"));
+
+        let merged = Aliases::new()
+            .alias("a", "b")
+            .alias("c", "d")
+            .alias("b", "c");
+        assert!(merged.are_aliases("a", "d"));
+    }
+
+    #[test]
+    fn test_find_best_match_for_name() {
+        let aliases = Aliases::new().alias("delete", "remove");
+        let commands = ["add", "remove", "deletes"];
+        assert_eq!(
+            aliases.find_best_match_for_name(commands.iter(), "delete", None),
+            Some("remove".to_string())
+        );
+        assert_eq!(
+            aliases.find_best_match_for_name(["remove", "Delete"].iter(), "delete", None),
+            Some("Delete".to_string())
+        );
+        assert_eq!(
+            aliases.find_best_match_for_name(["DELETE", "delete"].iter(), "delete", None),
+            Some("delete".to_string())
+        );
+        assert_eq!(
+            aliases.find_best_match_for_name(commands.iter(), "ad", None),
+            Some("add".to_string())
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Distance computation with caller-provided allocators.
+//!
+//! Requires the nightly-only `allocator_api` feature of the standard library,
+//! enabled through this crate's `allocator_api` feature. Only the scratch
+//! space of distance computations is covered; the indexes still allocate
+//! from the global heap.
+
+use std::alloc::Allocator;
+
+use crate::buffer::{buffered_distance, Scratch};
+
+/// Like [`DistanceBuffer`](crate::DistanceBuffer), with its scratch space
+/// allocated with `A`, e.g. from an arena or a pool.
+#[derive(Clone, Debug)]
+pub struct DistanceBufferIn<A: Allocator> {
+    a: Vec<char, A>,
+    b: Vec<char, A>,
+    row: Vec<usize, A>,
+}
+
+impl<A: Allocator + Clone> DistanceBufferIn<A> {
+    /// Creates an empty buffer allocating with `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        DistanceBufferIn {
+            a: Vec::new_in(alloc.clone()),
+            b: Vec::new_in(alloc.clone()),
+            row: Vec::new_in(alloc),
+        }
+    }
+}
+
+impl<A: Allocator> DistanceBufferIn<A> {
+    /// Finds the Levenshtein distance between two strings, like
+    /// [`lev_distance`](crate::lev_distance).
+    pub fn lev_distance(&mut self, a: &str, b: &str) -> usize {
+        buffered_distance(a, b, None, &mut self.a, &mut self.b, &mut self.row)
+    }
+
+    /// Finds the Levenshtein distance between two strings if it is at most
+    /// `limit`, like [`lev_distance_at_most`](crate::lev_distance_at_most).
+    pub fn lev_distance_at_most(&mut self, a: &str, b: &str, limit: usize) -> Option<usize> {
+        let dist = buffered_distance(a, b, Some(limit), &mut self.a, &mut self.b, &mut self.row);
+        (dist <= limit).then_some(dist)
+    }
+}
+
+impl<T, A: Allocator> Scratch<T> for Vec<T, A> {
+    fn refill(&mut self, items: impl IntoIterator<Item = T>) -> &mut [T] {
+        self.clear();
+        self.extend(items);
+        self
+    }
+}
+
+/// Finds the Levenshtein distance between two strings, allocating any
+/// scratch space with `alloc`.
+pub fn lev_distance_in<A: Allocator + Clone>(a: &str, b: &str, alloc: A) -> usize {
+    DistanceBufferIn::new_in(alloc).lev_distance(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+    use std::alloc::Global;
+
+    #[test]
+    fn test_lev_distance_in() {
+        let long_a = "abc".repeat(30);
+        let long_b = "acb".repeat(30);
+        let pairs = [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("Märy", "Mary"),
+            ("abc", ""),
+            ("日本語", "日本人"),
+            (long_a.as_str(), long_b.as_str()),
+        ];
+        let mut buffer = DistanceBufferIn::new_in(Global);
+        for (a, b) in pairs {
+            assert_eq!(lev_distance_in(a, b, Global), lev_distance(a, b));
+            assert_eq!(buffer.lev_distance(a, b), lev_distance(a, b));
+            assert_eq!(buffer.lev_distance(b, a), lev_distance(a, b));
+        }
+        assert_eq!(buffer.lev_distance_at_most("kitten", "sitting", 2), None);
+        assert_eq!(
+            buffer.lev_distance_at_most("ab", "abc", usize::MAX),
+            Some(1)
+        );
+        assert_eq!(buffer.lev_distance_at_most("日本語", "日本人", 1), Some(1));
+    }
+}
@@ -0,0 +1,440 @@
+//! Approximate substring search, finding where a pattern occurs in a longer
+//! text with a few errors.
+
+use std::ops::Range;
+
+use crate::kernel::{PatternMasks, MYERS_MAX_LEN};
+
+/// An approximate occurrence of a pattern in a text, as found by
+/// [`find_approx`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    /// The byte offset in the text where the occurrence starts.
+    pub start: usize,
+    /// The byte offset in the text where the occurrence ends.
+    pub end: usize,
+    /// The edit distance between the pattern and the occurrence.
+    pub distance: usize,
+}
+
+impl Match {
+    /// The byte range of the occurrence in the text.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Finds the approximate occurrences of `needle` in `haystack` with at most
+/// `max_errors` edits, in order.
+///
+/// Where occurrences overlap, only the one with the fewest edits is
+/// reported, the one of these closest to the length of `needle` being the
+/// best, and the first on ties. An empty needle occurs nowhere.
+///
+/// Ending positions are found with Sellers' algorithm, bit-parallel as in
+/// Myers for needles of up to 64 chars, in time linear in the haystack.
+pub fn find_approx(haystack: &str, needle: &str, max_errors: usize) -> Vec<Match> {
+    Needle::new(needle).find(haystack, max_errors)
+}
+
+/// Finds the lines approximately containing `query` with at most
+/// `max_errors` edits, yielding the number of every such line, starting at
+/// one as in `grep`, along with the byte range of the best occurrence in
+/// the line and its distance.
+///
+/// The best occurrence has the fewest edits, and is the first on ties. The
+/// query is preprocessed once for all lines, and lines are scanned lazily,
+/// so this can back the fuzzy search of an editor or a log viewer.
+pub fn fuzzy_grep<I>(
+    lines: I,
+    query: &str,
+    max_errors: usize,
+) -> impl Iterator<Item = (usize, Range<usize>, usize)>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let needle = Needle::new(query);
+    lines
+        .into_iter()
+        .zip(1..)
+        .filter_map(move |(line, line_no)| {
+            let best = needle
+                .find(line.as_ref(), max_errors)
+                .into_iter()
+                .min_by_key(|m| m.distance)?;
+            Some((line_no, best.range(), best.distance))
+        })
+}
+
+/// A set of patterns, each with its own error budget, searched for in a
+/// text at once.
+///
+/// Patterns of up to 64 chars are packed side by side into machine words,
+/// so that a single bit-parallel pass over the text serves several of them,
+/// and the text is decoded only once for all patterns.
+#[derive(Default)]
+pub struct PatternSet {
+    patterns: Vec<(Vec<char>, usize)>,
+    /// Groups of short patterns searched together, with the masks of their
+    /// concatenation and their ids.
+    packs: Vec<(PatternMasks, Vec<usize>)>,
+    /// The ids of the patterns too long to pack.
+    long: Vec<usize>,
+}
+
+impl PatternSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        PatternSet::default()
+    }
+
+    /// The number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Whether the set has no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Adds `pattern`, to be found with at most `max_errors` edits,
+    /// returning its id. Empty patterns occur nowhere.
+    pub fn insert(&mut self, pattern: &str, max_errors: usize) -> usize {
+        let id = self.patterns.len();
+        let len = pattern.chars().count();
+        self.patterns.push((pattern.chars().collect(), max_errors));
+        if len > MYERS_MAX_LEN {
+            self.long.push(id);
+            return id;
+        } else if len == 0 {
+            return id;
+        }
+        let packed_len =
+            |ids: &[usize]| -> usize { ids.iter().map(|&id| self.patterns[id].0.len()).sum() };
+        let mut ids = match self.packs.last() {
+            Some((_, ids)) if packed_len(ids) + len <= MYERS_MAX_LEN => {
+                self.packs.pop().map(|(_, ids)| ids).unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        ids.push(id);
+        let packed: Vec<char> = ids
+            .iter()
+            .flat_map(|&id| self.patterns[id].0.iter().copied())
+            .collect();
+        self.packs.push((PatternMasks::new(&packed), ids));
+        id
+    }
+
+    /// Finds the approximate occurrences of every pattern in `haystack`,
+    /// as [`find_approx`] would, along with the ids of their patterns,
+    /// ordered by where they start and then by id.
+    pub fn find(&self, haystack: &str) -> Vec<(usize, Match)> {
+        let text: Vec<(usize, char)> = haystack.char_indices().collect();
+        let mut found = Vec::new();
+        let mut collect = |id: usize, scores: &[usize]| {
+            let (needle, max_errors) = &self.patterns[id];
+            let occurrences = occurrences(needle, haystack, &text, scores, *max_errors);
+            found.extend(occurrences.into_iter().map(|m| (id, m)));
+        };
+        for (masks, ids) in &self.packs {
+            let lanes: Vec<usize> = ids.iter().map(|&id| self.patterns[id].0.len()).collect();
+            let scores = end_scores(masks, &lanes, chars(&text));
+            for (&id, scores) in ids.iter().zip(&scores) {
+                collect(id, scores);
+            }
+        }
+        for &id in &self.long {
+            collect(id, &sellers(&self.patterns[id].0, chars(&text)));
+        }
+        found.sort_by_key(|(id, m)| (m.start, *id));
+        found
+    }
+}
+
+impl<S: AsRef<str>> Extend<(S, usize)> for PatternSet {
+    fn extend<I: IntoIterator<Item = (S, usize)>>(&mut self, patterns: I) {
+        for (pattern, max_errors) in patterns {
+            self.insert(pattern.as_ref(), max_errors);
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, usize)> for PatternSet {
+    fn from_iter<I: IntoIterator<Item = (S, usize)>>(patterns: I) -> Self {
+        let mut set = PatternSet::new();
+        set.extend(patterns);
+        set
+    }
+}
+
+/// A pattern prepared for searching.
+struct Needle {
+    chars: Vec<char>,
+    /// The masks for [`end_scores`], unless the pattern is too long for them.
+    masks: Option<PatternMasks>,
+}
+
+impl Needle {
+    fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        let masks = (chars.len() <= MYERS_MAX_LEN).then(|| PatternMasks::new(&chars));
+        Needle { chars, masks }
+    }
+
+    /// See [`find_approx`].
+    fn find(&self, haystack: &str, max_errors: usize) -> Vec<Match> {
+        if self.chars.is_empty() {
+            return Vec::new();
+        }
+        let text: Vec<(usize, char)> = haystack.char_indices().collect();
+        let scores = match &self.masks {
+            Some(masks) => end_scores(masks, &[self.chars.len()], chars(&text)).remove(0),
+            None => sellers(&self.chars, chars(&text)),
+        };
+        occurrences(&self.chars, haystack, &text, &scores, max_errors)
+    }
+}
+
+fn chars(text: &[(usize, char)]) -> impl Iterator<Item = char> + '_ {
+    text.iter().map(|&(_, c)| c)
+}
+
+/// The occurrences of `needle` in `haystack` with at most `max_errors`
+/// edits, given the [`end_scores`] of `needle` in `text`, which holds the
+/// chars of `haystack`.
+fn occurrences(
+    needle: &[char],
+    haystack: &str,
+    text: &[(usize, char)],
+    scores: &[usize],
+    max_errors: usize,
+) -> Vec<Match> {
+    // Occurrences as (start, end, distance) in chars.
+    let mut occurrences: Vec<(usize, usize, usize)> = Vec::new();
+    for (end, distance) in best_ends(scores, max_errors) {
+        let start = end - occurrence_len(needle, &text[..end], distance);
+        let rank = |(start, end, distance): (usize, usize, usize)| {
+            (distance, (end - start).abs_diff(needle.len()))
+        };
+        match occurrences.last_mut() {
+            Some(last) if start < last.1 => {
+                if rank((start, end, distance)) < rank(*last) {
+                    *last = (start, end, distance);
+                }
+            }
+            _ => occurrences.push((start, end, distance)),
+        }
+    }
+    let offset = |i: usize| text.get(i).map_or(haystack.len(), |&(offset, _)| offset);
+    occurrences
+        .into_iter()
+        .map(|(start, end, distance)| Match {
+            start: offset(start),
+            end: offset(end),
+            distance,
+        })
+        .collect()
+}
+
+/// The least edit distance between every pattern packed into `masks` and a
+/// substring of `text` ending after every char of `text`.
+///
+/// The patterns occupy consecutive lanes of bits from the lowest up, one per
+/// length in `lanes`, which add up to at most [`MYERS_MAX_LEN`]. Carries
+/// never cross from one lane into the next, so a single run of Myers'
+/// algorithm serves them all.
+fn end_scores(
+    masks: &PatternMasks,
+    lanes: &[usize],
+    text: impl Iterator<Item = char>,
+) -> Vec<Vec<usize>> {
+    let (mut firsts, mut lasts, mut offset) = (0u64, Vec::with_capacity(lanes.len()), 0);
+    for &len in lanes {
+        firsts |= 1 << offset;
+        lasts.push(1u64 << (offset + len - 1));
+        offset += len;
+    }
+    let tops: u64 = lasts.iter().fold(0, |tops, last| tops | last);
+    let mut scores: Vec<Vec<usize>> = vec![Vec::new(); lanes.len()];
+    let mut score: Vec<usize> = lanes.to_vec();
+    let (mut pv, mut mv) = (!0u64, 0u64);
+    for c in text {
+        let eq = masks.get(c);
+        let xv = eq | mv;
+        let x = eq & pv;
+        // `x + pv` within every lane, dropping the carry out of its top bit.
+        let sum = (x & !tops).wrapping_add(pv & !tops) ^ ((x ^ pv) & tops);
+        let xh = (sum ^ pv) | eq;
+        let ph = mv | !(xh | pv);
+        let mh = pv & xh;
+        for ((score, scores), &last) in score.iter_mut().zip(&mut scores).zip(&lasts) {
+            if ph & last != 0 {
+                *score += 1;
+            } else if mh & last != 0 {
+                *score -= 1;
+            }
+            scores.push(*score);
+        }
+        // Unlike in `kernel::myers`, the top row stays zero, since an
+        // occurrence may start anywhere.
+        let (ph, mh) = ((ph << 1) & !firsts, (mh << 1) & !firsts);
+        pv = mh | !(xv | ph);
+        mv = ph & xv;
+    }
+    scores
+}
+
+/// Like [`end_scores`], one DP column per char of `text`.
+fn sellers(pattern: &[char], text: impl Iterator<Item = char>) -> Vec<usize> {
+    let mut column: Vec<usize> = (0..=pattern.len()).collect();
+    text.map(|c| {
+        let mut diagonal = 0;
+        for (i, &p) in pattern.iter().enumerate() {
+            let above = column[i + 1];
+            column[i + 1] = if p == c {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(column[i])
+            };
+            diagonal = above;
+        }
+        column[pattern.len()]
+    })
+    .collect()
+}
+
+/// The ends, exclusive and in chars, of the occurrences with at most
+/// `max_errors` edits, along with their distances: the local minima of
+/// `scores`, all of them where equal neighbors tie.
+fn best_ends(scores: &[usize], max_errors: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..scores.len()).filter_map(move |i| {
+        let score = scores[i];
+        let falls = i == 0 || scores[i - 1] >= score;
+        let rises = scores.get(i + 1).is_none_or(|&next| next >= score);
+        (score <= max_errors && falls && rises).then_some((i + 1, score))
+    })
+}
+
+/// The length in chars of the occurrence of `pattern` with `distance` edits
+/// ending at the end of `text`, the closest to the length of `pattern` and
+/// the shortest on ties.
+fn occurrence_len(pattern: &[char], text: &[(usize, char)], distance: usize) -> usize {
+    // The DP of the reversed pattern against the reversed text, where
+    // `column[i]` ends up as the distance between the last `i` chars of the
+    // pattern and the last `len` chars of the text.
+    let mut column: Vec<usize> = (0..=pattern.len()).collect();
+    let mut best = (column[pattern.len()] == distance).then_some(0);
+    let max_len = (pattern.len() + distance).min(text.len());
+    for (len, &(_, c)) in (1..=max_len).zip(text.iter().rev()) {
+        let mut diagonal = column[0];
+        column[0] = len;
+        for (i, &p) in pattern.iter().rev().enumerate() {
+            let left = column[i + 1];
+            column[i + 1] = if p == c {
+                diagonal
+            } else {
+                1 + diagonal.min(left).min(column[i])
+            };
+            diagonal = left;
+        }
+        let closer = |best: usize| len.abs_diff(pattern.len()) < best.abs_diff(pattern.len());
+        if column[pattern.len()] == distance && best.is_none_or(closer) {
+            best = Some(len);
+        }
+    }
+    best.unwrap_or(max_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_approx() {
+        let text = "let recieve = receive_all(buffer);";
+        let matches = find_approx(text, "receive", 2);
+        let found: Vec<(&str, usize)> = matches
+            .iter()
+            .map(|m| (&text[m.range()], m.distance))
+            .collect();
+        assert_eq!(found, [("recieve", 2), ("receive", 0)]);
+
+        let matches = find_approx("xabcx", "abc", 1);
+        assert_eq!(
+            matches,
+            [Match {
+                start: 1,
+                end: 4,
+                distance: 0
+            }]
+        );
+        assert_eq!(find_approx("zbc", "abc", 1)[0].range(), 0..3);
+        assert_eq!(find_approx("Die Größe", "größe", 1)[0].range(), 4..11);
+        assert!(find_approx("hello", "world", 1).is_empty());
+        assert!(find_approx("hello", "", 1).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_grep() {
+        let log = "starting server\nconection refused\nretrying\nconnection refused";
+        let hits: Vec<_> = fuzzy_grep(log.lines(), "connection", 1).collect();
+        assert_eq!(hits, [(2, 0..9, 1), (4, 0..10, 0)]);
+        assert_eq!(fuzzy_grep(log.lines(), "shutdown", 1).count(), 0);
+    }
+
+    #[test]
+    fn test_pattern_set() {
+        let text = "fn main() { let reslt = cmopute(input); retrun reslt; }";
+        let keywords = [
+            ("result", 1),
+            ("compute", 2),
+            ("return", 2),
+            ("missing", 2),
+            ("input", 1),
+        ];
+        let set: PatternSet = keywords.iter().copied().collect();
+        assert_eq!(set.len(), 5);
+        let found: Vec<(&str, &str)> = set
+            .find(text)
+            .into_iter()
+            .map(|(id, m)| (keywords[id].0, &text[m.range()]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("result", "reslt"),
+                ("compute", "cmopute"),
+                ("input", "input"),
+                ("return", "retrun"),
+                ("result", "reslt"),
+            ]
+        );
+        for (id, &(keyword, max_errors)) in keywords.iter().enumerate() {
+            let expected = find_approx(text, keyword, max_errors);
+            let actual: Vec<_> = set
+                .find(text)
+                .into_iter()
+                .filter(|&(i, _)| i == id)
+                .map(|(_, m)| m)
+                .collect();
+            assert_eq!(actual, expected, "{:?}", keyword);
+        }
+    }
+
+    #[test]
+    fn test_long_needle() {
+        let needle = "a".repeat(40) + &"b".repeat(40);
+        let text = format!("xx{}c{}xx", "a".repeat(40), "b".repeat(39));
+        let matches = find_approx(&text, &needle, 2);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].distance, 1);
+        assert_eq!(matches[0].range(), 2..82);
+        assert_eq!(
+            sellers(&['a', 'b'], "xab".chars()),
+            end_scores(&PatternMasks::new(&['a', 'b']), &[2], "xab".chars())[0]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Batch operations allocating from a bump arena.
+//!
+//! A diagnostic pass may produce hundreds of suggestions. Allocating the
+//! result strings and DP rows from one [`Bump`] lets the whole batch be freed
+//! at once with [`Bump::reset`].
+
+use std::cell::RefCell;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+
+use crate::buffer::{buffered_distance, Scratch};
+use crate::{find_best_match_in_context, Pipeline, QueryContext, QueryStats, StringMetric};
+
+/// A batch context allocating all of its temporaries and results from an
+/// arena.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchContext<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'bump> BatchContext<'bump> {
+    /// Creates a context allocating from `bump`.
+    pub fn new(bump: &'bump Bump) -> Self {
+        BatchContext { bump }
+    }
+
+    fn metric(&self) -> ArenaLevenshtein<'bump> {
+        ArenaLevenshtein(RefCell::new(ArenaScratch {
+            a: BumpVec::new_in(self.bump),
+            b: BumpVec::new_in(self.bump),
+            row: BumpVec::new_in(self.bump),
+        }))
+    }
+
+    /// Finds the Levenshtein distance between two strings, with the DP row in
+    /// the arena.
+    pub fn lev_distance(&self, a: &str, b: &str) -> usize {
+        self.metric().0.get_mut().distance(a, b, None)
+    }
+
+    /// Finds the best match like
+    /// [`find_best_match_for_name`](crate::find_best_match_for_name), returning
+    /// a copy of it that lives in the arena.
+    ///
+    /// The distances are computed with their scratch space in the arena.
+    pub fn find_best_match_for_name<T>(
+        &self,
+        iter_names: impl Iterator<Item = T>,
+        lookup: &str,
+        dist: Option<usize>,
+    ) -> Option<&'bump str>
+    where
+        T: AsRef<str>,
+    {
+        let pipeline = Pipeline {
+            threshold: dist.into(),
+            ..Pipeline::default()
+        };
+        let max_dist = pipeline.max_dist_for(lookup) as f64;
+        let (best, _) = find_best_match_in_context(
+            iter_names,
+            lookup,
+            &pipeline,
+            &self.metric(),
+            max_dist,
+            &mut QueryContext::default(),
+        )?;
+        Some(self.bump.alloc_str(best.as_ref()))
+    }
+}
+
+/// The scratch space of a distance computation, in an arena.
+struct ArenaScratch<'bump> {
+    a: BumpVec<'bump, char>,
+    b: BumpVec<'bump, char>,
+    row: BumpVec<'bump, usize>,
+}
+
+impl ArenaScratch<'_> {
+    fn distance(&mut self, a: &str, b: &str, limit: Option<usize>) -> usize {
+        buffered_distance(a, b, limit, &mut self.a, &mut self.b, &mut self.row)
+    }
+}
+
+impl<T> Scratch<T> for BumpVec<'_, T> {
+    fn refill(&mut self, items: impl IntoIterator<Item = T>) -> &mut [T] {
+        self.clear();
+        self.extend(items);
+        self
+    }
+}
+
+/// The Levenshtein distance with its scratch space reused across calls.
+struct ArenaLevenshtein<'bump>(RefCell<ArenaScratch<'bump>>);
+
+impl StringMetric for ArenaLevenshtein<'_> {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        self.0.borrow_mut().distance(a, b, None) as f64
+    }
+
+    fn distance_within(&self, a: &str, b: &str, limit: f64, _: &mut QueryStats) -> f64 {
+        self.0.borrow_mut().distance(a, b, Some(limit as usize)) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_best_match_for_name, lev_distance};
+
+    #[test]
+    fn test_lev_distance() {
+        let bump = Bump::new();
+        let ctx = BatchContext::new(&bump);
+        let long_a = "abc".repeat(30);
+        let long_b = "acb".repeat(30);
+        for (a, b) in [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("Märy", "Mary"),
+            (long_a.as_str(), long_b.as_str()),
+        ] {
+            assert_eq!(ctx.lev_distance(a, b), lev_distance(a, b));
+        }
+    }
+
+    #[test]
+    fn test_find_best_match_for_name() {
+        let mut bump = Bump::new();
+        {
+            let ctx = BatchContext::new(&bump);
+            let input = ["aaab", "aaabc", "a_longer_variable_name", "größe"];
+            for lookup in [
+                "aaaa",
+                "AAAB",
+                "1111111111",
+                "a_variable_longer_name",
+                "grösse",
+            ] {
+                assert_eq!(
+                    ctx.find_best_match_for_name(input.iter(), lookup, None),
+                    find_best_match_for_name(input.iter(), lookup, None).as_deref()
+                );
+            }
+            // An exact match wins over an earlier case-insensitive one.
+            assert_eq!(
+                ctx.find_best_match_for_name(["PRINT", "print"].iter(), "print", None),
+                Some("print")
+            );
+            assert_eq!(
+                ctx.find_best_match_for_name(["b", "B"].iter(), "B", None),
+                Some("B")
+            );
+            assert!(bump.allocated_bytes() > 0);
+        }
+        bump.reset();
+    }
+}
@@ -0,0 +1,106 @@
+//! Test helpers asserting on suggestions.
+
+use std::fmt::Write;
+
+use crate::{find_best_match_for_name, lev_distance};
+
+/// Asserts that [`find_best_match_for_name`] suggests `expected` for
+/// `lookup` among `candidates`.
+///
+/// On failure, the message lists the distance from the lookup to every
+/// candidate.
+///
+/// ```
+/// use lev_distance::assert_suggestion;
+///
+/// assert_suggestion!(["lookup", "locate"], "lookpu", "lookup");
+/// ```
+#[macro_export]
+macro_rules! assert_suggestion {
+    ($candidates:expr, $lookup:expr, $expected:expr $(,)?) => {
+        if let Err(message) = $crate::__check_suggestion($candidates, $lookup, Some($expected)) {
+            panic!("{}", message);
+        }
+    };
+}
+
+/// Asserts that [`find_best_match_for_name`] suggests nothing for `lookup`
+/// among `candidates`.
+///
+/// ```
+/// use lev_distance::assert_no_suggestion;
+///
+/// assert_no_suggestion!(["lookup", "locate"], "xyzzy");
+/// ```
+#[macro_export]
+macro_rules! assert_no_suggestion {
+    ($candidates:expr, $lookup:expr $(,)?) => {
+        if let Err(message) = $crate::__check_suggestion($candidates, $lookup, None) {
+            panic!("{}", message);
+        }
+    };
+}
+
+#[doc(hidden)]
+pub fn __check_suggestion<I>(
+    candidates: I,
+    lookup: &str,
+    expected: Option<&str>,
+) -> Result<(), String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let candidates: Vec<String> = candidates
+        .into_iter()
+        .map(|c| c.as_ref().to_string())
+        .collect();
+    let found = find_best_match_for_name(candidates.iter(), lookup, None);
+    if found.as_deref() == expected {
+        return Ok(());
+    }
+
+    let show = |s: Option<&str>| s.map_or("nothing".to_string(), |s| format!("`{}`", s));
+    let mut message = format!(
+        "assertion failed: suggestion for `{}`\n  expected: {}\n     found: {}\n distances:",
+        lookup,
+        show(expected),
+        show(found.as_deref()),
+    );
+    for candidate in &candidates {
+        let _ = write!(
+            message,
+            "\n    `{}`: {}",
+            candidate,
+            lev_distance(lookup, candidate)
+        );
+    }
+    Err(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_suggestion() {
+        assert_suggestion!(vec!["lookup".to_string()], "lookpu", "lookup");
+        assert_suggestion!(["aaab", "aaabc"].iter(), "aaaa", "aaab");
+        assert_no_suggestion!(["aaab", "aaabc"], "1111111111");
+    }
+
+    #[test]
+    fn test_failure_message() {
+        let message = __check_suggestion(["lookup", "look"], "lookpu", Some("look")).unwrap_err();
+        assert_eq!(
+            message,
+            "assertion failed: suggestion for `lookpu`\n  expected: `look`\n     found: `lookup`\n distances:\n    `lookup`: 2\n    `look`: 2"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "found: nothing")]
+    fn test_assert_suggestion_panics() {
+        assert_suggestion!(["abc"], "xyz", "abc");
+    }
+}
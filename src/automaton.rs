@@ -0,0 +1,137 @@
+//! A Levenshtein automaton.
+//!
+//! The automaton for a query and a maximum distance accepts exactly the words
+//! within that distance of the query. Its state after reading a prefix is the
+//! DP row of the prefix against the query, with every entry above the
+//! maximum clamped, so walking a sorted dictionary or a trie with it shares
+//! the work for common prefixes, and prunes a whole subtree as soon as its
+//! state can no longer match.
+
+use std::cmp;
+
+use crate::Distance;
+
+/// An automaton accepting the words within a distance of a query.
+#[derive(Clone, Debug)]
+pub struct LevenshteinAutomaton {
+    query: Vec<char>,
+    max_dist: usize,
+}
+
+/// The state of a [`LevenshteinAutomaton`] after reading some prefix.
+///
+/// States are small and hashable, so they can be cached, e.g. to build a
+/// DFA lazily.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AutomatonState(Vec<usize>);
+
+impl LevenshteinAutomaton {
+    /// Creates the automaton accepting words within `max_dist` of `query`.
+    pub fn new(query: &str, max_dist: usize) -> Self {
+        LevenshteinAutomaton {
+            query: query.chars().collect(),
+            max_dist,
+        }
+    }
+
+    /// The state before reading anything.
+    pub fn start(&self) -> AutomatonState {
+        AutomatonState(
+            (0..=self.query.len())
+                .map(|j| cmp::min(j, self.max_dist + 1))
+                .collect(),
+        )
+    }
+
+    /// The state after reading `c` in `state`.
+    pub fn step(&self, state: &AutomatonState, c: char) -> AutomatonState {
+        let mut next = Vec::with_capacity(state.0.len());
+        self.step_into(&state.0, c, &mut next);
+        AutomatonState(next)
+    }
+
+    /// Like [`step`](Self::step), writing the new row into `next`.
+    pub(crate) fn step_into(&self, prev: &[usize], c: char, next: &mut Vec<usize>) {
+        let over = self.max_dist + 1;
+        next.clear();
+        next.push(cmp::min(prev[0] + 1, over));
+        for (j, &q) in self.query.iter().enumerate() {
+            let substitution = prev[j] + usize::from(q != c);
+            let deletion = prev[j + 1] + 1;
+            let insertion = next[j] + 1;
+            next.push(substitution.min(deletion).min(insertion).min(over));
+        }
+    }
+
+    /// Whether the prefix read so far is within the distance of the query.
+    pub fn is_match(&self, state: &AutomatonState) -> bool {
+        self.distance(state).is_some()
+    }
+
+    /// The distance from the prefix read so far to the query, if within the
+    /// maximum.
+    pub fn distance(&self, state: &AutomatonState) -> Option<Distance> {
+        let dist = state.0[self.query.len()];
+        (dist <= self.max_dist).then_some(Distance(dist))
+    }
+
+    /// Whether any word starting with the prefix read so far can match.
+    pub fn can_match(&self, state: &AutomatonState) -> bool {
+        state.0.iter().any(|&d| d <= self.max_dist)
+    }
+
+    /// Runs the automaton over `word`, returning its distance to the query
+    /// if within the maximum.
+    pub fn matches(&self, word: &str) -> Option<Distance> {
+        let mut state = self.start();
+        for c in word.chars() {
+            if !self.can_match(&state) {
+                return None;
+            }
+            state = self.step(&state, c);
+        }
+        self.distance(&state)
+    }
+}
+
+impl AutomatonState {
+    pub(crate) fn row(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_matches() {
+        let words = [
+            "", "a", "kitten", "sitting", "kitchen", "über", "uber", "mitten",
+        ];
+        for query in ["", "kitten", "uber", "xyz"] {
+            for max_dist in 0..4 {
+                let automaton = LevenshteinAutomaton::new(query, max_dist);
+                for word in words {
+                    let dist = lev_distance(query, word);
+                    let expected = (dist <= max_dist).then_some(Distance(dist));
+                    assert_eq!(automaton.matches(word), expected, "{:?} {:?}", query, word);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_states() {
+        let automaton = LevenshteinAutomaton::new("cat", 1);
+        let start = automaton.start();
+        let c = automaton.step(&start, 'c');
+        assert!(!automaton.is_match(&c));
+        assert!(automaton.can_match(&c));
+        let x = automaton.step(&automaton.step(&start, 'x'), 'x');
+        assert!(!automaton.can_match(&x));
+        // Clamping makes states reached by different dead prefixes equal.
+        assert_eq!(x, automaton.step(&automaton.step(&start, 'y'), 'z'));
+    }
+}
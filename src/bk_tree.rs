@@ -0,0 +1,197 @@
+//! A BK-tree of candidates for repeated queries.
+//!
+//! Every child hangs off its parent by their distance, so by the triangle
+//! inequality a query only needs to descend into the children whose edge is
+//! within `max_dist` of the query's distance to the parent.
+
+use crate::{kernel, Distance};
+
+/// A metric tree of words, built once and queried many times.
+#[derive(Clone, Debug, Default)]
+pub struct BkTree {
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    word: String,
+    /// The distance to each child and its index in `nodes`.
+    children: Vec<(usize, usize)>,
+}
+
+impl BkTree {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        BkTree::default()
+    }
+
+    /// The number of words in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Inserts `word`, returning whether it was not already in the tree.
+    pub fn insert(&mut self, word: impl Into<String>) -> bool {
+        let word = word.into();
+        let mut node = 0;
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::new(word));
+            return true;
+        }
+        loop {
+            let dist = kernel::distance(&word, &self.nodes[node].word, None);
+            if dist == 0 {
+                return false;
+            }
+            match self.nodes[node].children.iter().find(|&&(d, _)| d == dist) {
+                Some(&(_, child)) => node = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes[node].children.push((dist, child));
+                    self.nodes.push(Node::new(word));
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Finds every word within `max_dist` of `query`, closest first; words at
+    /// the same distance keep their insertion order.
+    pub fn find_within(&self, query: &str, max_dist: usize) -> Vec<(&str, Distance)> {
+        let mut found: Vec<(usize, usize)> = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let Node { word, children } = &self.nodes[node];
+            let dist = kernel::distance(query, word, None);
+            if dist <= max_dist {
+                found.push((dist, node));
+            }
+            stack.extend(
+                children
+                    .iter()
+                    .filter(|&&(d, _)| d.abs_diff(dist) <= max_dist)
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|(dist, node)| (self.nodes[node].word.as_str(), Distance(dist)))
+            .collect()
+    }
+
+    /// Finds the `k` words closest to `query`, closest first; words at the
+    /// same distance keep their insertion order.
+    ///
+    /// Subtrees are skipped once they cannot hold anything closer than the
+    /// `k`th word found so far.
+    pub fn nearest(&self, query: &str, k: usize) -> Vec<(&str, Distance)> {
+        // Sorted by distance and then by node, which is the insertion order.
+        let mut nearest: Vec<(usize, usize)> = Vec::with_capacity(k);
+        // Every node with a lower bound on its distance to the query.
+        let mut stack = if self.nodes.is_empty() || k == 0 {
+            vec![]
+        } else {
+            vec![(0, 0)]
+        };
+        while let Some((node, bound)) = stack.pop() {
+            let tau = if nearest.len() == k {
+                nearest[k - 1].0
+            } else {
+                usize::MAX
+            };
+            if bound > tau {
+                continue;
+            }
+            let Node { word, children } = &self.nodes[node];
+            let dist = kernel::distance(query, word, None);
+            if nearest.len() < k || (dist, node) < nearest[k - 1] {
+                let pos = nearest.partition_point(|&found| found < (dist, node));
+                nearest.insert(pos, (dist, node));
+                nearest.truncate(k);
+            }
+            stack.extend(children.iter().map(|&(d, child)| (child, d.abs_diff(dist))));
+        }
+        nearest
+            .into_iter()
+            .map(|(dist, node)| (self.nodes[node].word.as_str(), Distance(dist)))
+            .collect()
+    }
+}
+
+impl Node {
+    fn new(word: String) -> Self {
+        Node {
+            word,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<S: Into<String>> Extend<S> for BkTree {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        for word in words {
+            self.insert(word);
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for BkTree {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> Self {
+        let mut tree = BkTree::new();
+        tree.extend(words);
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{check_find_within, scan};
+
+    const WORDS: &[&str] = &[
+        "book", "books", "cake", "boo", "boon", "cook", "cape", "cart", "print", "println",
+    ];
+    const QUERIES: &[&str] = &["bo", "book", "cale", "prnt", "zzzz"];
+
+    #[test]
+    fn test_find_within() {
+        let tree: BkTree = WORDS.iter().copied().collect();
+        assert_eq!(tree.len(), WORDS.len());
+        check_find_within(WORDS, QUERIES, 3, |query, max_dist| {
+            tree.find_within(query, max_dist)
+        });
+    }
+
+    #[test]
+    fn test_nearest() {
+        let tree: BkTree = WORDS.iter().copied().collect();
+        for &query in QUERIES {
+            for k in 0..5 {
+                let mut expected = scan(WORDS, query);
+                expected.truncate(k);
+                assert_eq!(tree.nearest(query, k), expected);
+            }
+        }
+        assert!(BkTree::new().nearest("a", 3).is_empty());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut tree = BkTree::new();
+        assert!(tree.find_within("a", 3).is_empty());
+        assert!(tree.insert("book"));
+        assert!(!tree.insert("book"));
+        assert!(tree.insert(String::from("boo")));
+        assert_eq!(tree.len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+//! Distances with reusable scratch space.
+
+use std::cmp;
+
+use crate::kernel;
+
+/// Scratch space for computing many distances without allocating for each.
+///
+/// The buffers grow to fit the longest inputs seen and are then reused, so
+/// scanning a large candidate set allocates only a handful of times.
+#[derive(Clone, Debug, Default)]
+pub struct DistanceBuffer {
+    a: Vec<char>,
+    b: Vec<char>,
+    row: Vec<usize>,
+}
+
+impl DistanceBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        DistanceBuffer::default()
+    }
+
+    /// Creates a buffer with room for strings of up to `len` chars.
+    pub fn with_capacity(len: usize) -> Self {
+        DistanceBuffer {
+            a: Vec::with_capacity(len),
+            b: Vec::with_capacity(len),
+            row: Vec::with_capacity(len + 1),
+        }
+    }
+
+    /// Finds the Levenshtein distance between two strings, like
+    /// [`lev_distance`](crate::lev_distance).
+    pub fn lev_distance(&mut self, a: &str, b: &str) -> usize {
+        buffered_distance(a, b, None, &mut self.a, &mut self.b, &mut self.row)
+    }
+
+    /// Finds the Levenshtein distance between two strings if it is at most
+    /// `limit`, like [`lev_distance_at_most`](crate::lev_distance_at_most).
+    pub fn lev_distance_at_most(&mut self, a: &str, b: &str, limit: usize) -> Option<usize> {
+        let dist = buffered_distance(a, b, Some(limit), &mut self.a, &mut self.b, &mut self.row);
+        (dist <= limit).then_some(dist)
+    }
+}
+
+/// Growable storage for the scratch space of a distance buffer.
+pub(crate) trait Scratch<T> {
+    /// Replaces the contents with `items` and returns them.
+    fn refill(&mut self, items: impl IntoIterator<Item = T>) -> &mut [T];
+}
+
+// Under `allocator_api`, the impl for `Vec<T, A>` covers the global heap.
+#[cfg(not(feature = "allocator_api"))]
+impl<T> Scratch<T> for Vec<T> {
+    fn refill(&mut self, items: impl IntoIterator<Item = T>) -> &mut [T] {
+        self.clear();
+        self.extend(items);
+        self
+    }
+}
+
+/// Finds the distance between `a` and `b` like
+/// [`kernel::distance`], decoding non-ASCII strings into `a_chars` and
+/// `b_chars` and keeping the DP row in `row`.
+pub(crate) fn buffered_distance<C, R>(
+    a: &str,
+    b: &str,
+    limit: Option<usize>,
+    a_chars: &mut C,
+    b_chars: &mut C,
+    row: &mut R,
+) -> usize
+where
+    C: Scratch<char>,
+    R: Scratch<usize>,
+{
+    let (a, b) = kernel::trim_common_affixes(a, b);
+    if a.is_ascii() && b.is_ascii() {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+        if short.len() <= kernel::MYERS_MAX_LEN {
+            return kernel::myers_bytes(short, long);
+        }
+        return distance_in_row(a, b, limit, row);
+    }
+    let a = a_chars.refill(a.chars());
+    let b = b_chars.refill(b.chars());
+    distance_in_row(a, b, limit, row)
+}
+
+/// The banded DP if `limit` cuts the work, or the full DP otherwise, with
+/// the row in `row`.
+fn distance_in_row<T, R>(a: &[T], b: &[T], limit: Option<usize>, row: &mut R) -> usize
+where
+    T: PartialEq,
+    R: Scratch<usize>,
+{
+    // No distance exceeds the length of the longer input.
+    let limit = limit.filter(|&limit| limit < cmp::max(a.len(), b.len()));
+    let row = row.refill(0..=b.len());
+    match limit {
+        Some(limit) => kernel::banded_in_row(a, b, limit, &mut 0, row).unwrap_or(limit + 1),
+        None => kernel::slice_in_row(a, b, row),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_distance_buffer() {
+        let long_a = "abc".repeat(30);
+        let long_b = "acb".repeat(30);
+        let pairs = [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("größe", "grosse"),
+            ("日本語", "日本人"),
+            (long_a.as_str(), long_b.as_str()),
+            ("", ""),
+        ];
+        let mut buffer = DistanceBuffer::new();
+        for (a, b) in pairs {
+            assert_eq!(
+                buffer.lev_distance(a, b),
+                lev_distance(a, b),
+                "{:?} {:?}",
+                a,
+                b
+            );
+            assert_eq!(
+                buffer.lev_distance(b, a),
+                lev_distance(a, b),
+                "{:?} {:?}",
+                b,
+                a
+            );
+        }
+        assert_eq!(buffer.lev_distance_at_most("kitten", "sitting", 3), Some(3));
+        assert_eq!(buffer.lev_distance_at_most("kitten", "sitting", 2), None);
+        assert_eq!(buffer.lev_distance_at_most("a", "abcdefghijkl", 2), None);
+        assert_eq!(
+            buffer.lev_distance_at_most("ab", "abc", usize::MAX),
+            Some(1)
+        );
+        assert_eq!(
+            buffer.lev_distance_at_most(&long_a, &long_b, usize::MAX),
+            Some(lev_distance(&long_a, &long_b))
+        );
+        assert_eq!(buffer.lev_distance_at_most(&long_a, &long_b, 3), None);
+        assert_eq!(
+            buffer.lev_distance_at_most("日本語日本語", "日本人日本語", 1),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Case-insensitive comparison.
+
+/// How the exact-match tier folds case before comparing.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum CaseFolding {
+    /// Unicode full case folding, so that `ß` matches `SS` and final `ς`
+    /// matches `Σ`, while the Turkish dotless `ı` stays distinct from `i`.
+    #[default]
+    Unicode,
+    /// Unicode full case folding with the Turkic mappings of the dotted and
+    /// dotless i, so that `I` matches `ı` and `İ` matches `i`.
+    Turkic,
+    /// Only ASCII letters are folded.
+    Ascii,
+    /// A custom predicate deciding whether two strings are equal up to case.
+    Custom(fn(&str, &str) -> bool),
+}
+
+impl CaseFolding {
+    /// Whether `a` and `b` are equal up to case, without allocating.
+    pub fn eq(self, a: &str, b: &str) -> bool {
+        match self {
+            // Folding is plain ASCII lowercasing when both sides are ASCII.
+            CaseFolding::Unicode if a.is_ascii() && b.is_ascii() => a.eq_ignore_ascii_case(b),
+            CaseFolding::Unicode => fold(a, false).eq(fold(b, false)),
+            CaseFolding::Turkic => fold(a, true).eq(fold(b, true)),
+            CaseFolding::Ascii => a.eq_ignore_ascii_case(b),
+            CaseFolding::Custom(eq) => eq(a, b),
+        }
+    }
+}
+
+impl CaseFolding {
+    /// The folded form of `s`, which [`CaseFolding::Custom`] leaves as is.
+    pub(crate) fn folded(self, s: &str) -> String {
+        match self {
+            CaseFolding::Unicode => fold(s, false).collect(),
+            CaseFolding::Turkic => fold(s, true).collect(),
+            CaseFolding::Ascii => s.to_ascii_lowercase(),
+            CaseFolding::Custom(_) => s.to_string(),
+        }
+    }
+}
+
+/// The case folding of `s`, approximated by lowercasing the uppercase form
+/// of each char, which also expands chars like `ß` and `ﬁ`.
+fn fold(s: &str, turkic: bool) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(move |c| {
+        let special: &'static [char] = match (c, turkic) {
+            ('I', true) => &['ı'],
+            ('İ', true) => &['i'],
+            ('ı', _) => &['ı'],
+            ('İ', false) => &['i', '\u{307}'],
+            _ => &[],
+        };
+        let general = special
+            .is_empty()
+            .then(|| c.to_uppercase().flat_map(char::to_lowercase));
+        special.iter().copied().chain(general.into_iter().flatten())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_folding() {
+        assert!(CaseFolding::Unicode.eq("Straße", "STRASSE"));
+        assert!(CaseFolding::Unicode.eq("ΟΔΟΣ", "οδος"));
+        assert!(!CaseFolding::Unicode.eq("ΟΔΟΣ", "οδoς"));
+        assert!(!CaseFolding::Unicode.eq("ı", "i"));
+        assert!(!CaseFolding::Unicode.eq("I", "ı"));
+        assert!(CaseFolding::Unicode.eq("println", "PrintLn"));
+        assert!(!CaseFolding::Unicode.eq("println", "print"));
+        // Only one side is ASCII, so the slow path still applies.
+        assert!(CaseFolding::Unicode.eq("\u{212A}elvin", "kelvin"));
+
+        assert!(CaseFolding::Turkic.eq("DİYARBAKIR", "diyarbakır"));
+        assert!(!CaseFolding::Turkic.eq("I", "i"));
+
+        assert!(CaseFolding::Ascii.eq("Hello", "hELLO"));
+        assert!(!CaseFolding::Ascii.eq("Straße", "STRASSE"));
+
+        assert_eq!(CaseFolding::Unicode.folded("Straße"), "strasse");
+        assert_eq!(CaseFolding::Turkic.folded("DİYARBAKIR"), "diyarbakır");
+        assert_eq!(CaseFolding::Ascii.folded("ÉCOLE"), "École");
+
+        let same_len = CaseFolding::Custom(|a, b| a.len() == b.len());
+        assert!(same_len.eq("abc", "xyz"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Agglomerative hierarchical clustering over edit distance.
+
+use std::cmp;
+
+use crate::lev_distance;
+
+/// How the distance between two clusters is derived from the distances
+/// between their members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Linkage {
+    /// The distance between the closest pair of members.
+    Single,
+    /// The distance between the farthest pair of members.
+    Complete,
+}
+
+/// A single step of the clustering, joining two clusters into one.
+///
+/// Clusters are numbered like in SciPy: the `n` input items are clusters
+/// `0..n`, and the cluster created by the `i`-th merge is `n + i`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// The smaller-numbered of the two merged clusters.
+    pub left: usize,
+    /// The larger-numbered of the two merged clusters.
+    pub right: usize,
+    /// The linkage distance between the two clusters.
+    pub distance: usize,
+    /// The number of items in the merged cluster.
+    pub size: usize,
+}
+
+/// The full merge history of a clustering, which can be cut at any
+/// granularity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dendrogram {
+    len: usize,
+    merges: Vec<Merge>,
+}
+
+impl Dendrogram {
+    /// The merges in the order they were made, with non-decreasing distances.
+    pub fn merges(&self) -> &[Merge] {
+        &self.merges
+    }
+
+    /// Cuts the dendrogram so that no clusters within `max_dist` of each
+    /// other remain separate.
+    pub fn cut(&self, max_dist: usize) -> Vec<Vec<usize>> {
+        let count = self
+            .merges
+            .iter()
+            .take_while(|merge| merge.distance <= max_dist)
+            .count();
+        self.flatten(count)
+    }
+
+    /// Cuts the dendrogram into (at most) `k` clusters.
+    pub fn cut_into(&self, k: usize) -> Vec<Vec<usize>> {
+        self.flatten(self.len.saturating_sub(cmp::max(k, 1)))
+    }
+
+    /// Applies the first `count` merges and returns the resulting clusters as
+    /// sorted lists of item indices, ordered by their first item.
+    fn flatten(&self, count: usize) -> Vec<Vec<usize>> {
+        let mut clusters: Vec<Option<Vec<usize>>> = (0..self.len).map(|i| Some(vec![i])).collect();
+        for merge in &self.merges[..count] {
+            let mut items = clusters[merge.left].take().unwrap_or_default();
+            items.extend(clusters[merge.right].take().unwrap_or_default());
+            items.sort_unstable();
+            clusters.push(Some(items));
+        }
+        let mut clusters: Vec<Vec<usize>> = clusters.into_iter().flatten().collect();
+        clusters.sort_unstable_by_key(|items| items[0]);
+        clusters
+    }
+}
+
+/// Clusters `items` by edit distance.
+///
+/// This computes the full pairwise distance matrix and is cubic in the number
+/// of items, so it is meant for exploring label sets of modest size.
+pub fn cluster<T>(items: &[T], linkage: Linkage) -> Dendrogram
+where
+    T: AsRef<str>,
+{
+    let n = items.len();
+    let mut dist = vec![vec![0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let d = lev_distance(items[i].as_ref(), items[j].as_ref());
+            dist[i][j] = d;
+            dist[j][i] = d;
+        }
+    }
+
+    // Slot `i` holds cluster `ids[i]` with `sizes[i]` items while active.
+    let mut ids: Vec<usize> = (0..n).collect();
+    let mut sizes = vec![1; n];
+    let mut active = vec![true; n];
+    let mut merges = Vec::with_capacity(n.saturating_sub(1));
+    for step in 0..n.saturating_sub(1) {
+        let mut closest: Option<(usize, usize)> = None;
+        for i in (0..n).filter(|&i| active[i]) {
+            for j in (i + 1..n).filter(|&j| active[j]) {
+                if closest.is_none_or(|(a, b)| dist[i][j] < dist[a][b]) {
+                    closest = Some((i, j));
+                }
+            }
+        }
+        let Some((a, b)) = closest else { break };
+
+        merges.push(Merge {
+            left: cmp::min(ids[a], ids[b]),
+            right: cmp::max(ids[a], ids[b]),
+            distance: dist[a][b],
+            size: sizes[a] + sizes[b],
+        });
+        for k in (0..n).filter(|&k| active[k] && k != a && k != b) {
+            let d = match linkage {
+                Linkage::Single => cmp::min(dist[a][k], dist[b][k]),
+                Linkage::Complete => cmp::max(dist[a][k], dist[b][k]),
+            };
+            dist[a][k] = d;
+            dist[k][a] = d;
+        }
+        ids[a] = n + step;
+        sizes[a] += sizes[b];
+        active[b] = false;
+    }
+
+    Dendrogram { len: n, merges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_cut() {
+        let items = ["color", "colour", "colours", "size", "sizes", "weight"];
+        let dendrogram = cluster(&items, Linkage::Single);
+        assert_eq!(dendrogram.merges().len(), 5);
+        assert_eq!(
+            dendrogram.merges()[0],
+            Merge {
+                left: 0,
+                right: 1,
+                distance: 1,
+                size: 2
+            }
+        );
+        assert_eq!(dendrogram.cut(1), vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(dendrogram.cut(0).len(), 6);
+        assert_eq!(dendrogram.cut_into(1), vec![vec![0, 1, 2, 3, 4, 5]]);
+    }
+
+    #[test]
+    fn test_cluster_linkage() {
+        // A chain where each neighbour is one edit apart.
+        let items = ["a", "ab", "abc", "abcd"];
+        assert_eq!(cluster(&items, Linkage::Single).cut(1).len(), 1);
+        assert_eq!(cluster(&items, Linkage::Complete).cut(1).len(), 2);
+        assert!(cluster::<&str>(&[], Linkage::Single).merges().is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! Confidence estimates for suggestions.
+//!
+//! A suggestion's raw distance says little on its own: one edit in a
+//! three-letter word is a bigger deal than one edit in a twenty-letter word,
+//! and a suggestion with several equally close rivals is a guess. These
+//! signals are combined by a logistic model into the estimated probability
+//! that the suggestion is what the user meant.
+
+use std::cmp;
+
+use crate::{kernel, Indexed, Pipeline, QueryContext, Threshold};
+
+/// The signals a [`Calibration`] is computed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SuggestionFeatures {
+    /// The edit distance between the lookup and the suggestion.
+    pub distance: usize,
+    /// The length of the lookup in characters.
+    pub lookup_len: usize,
+    /// The number of other candidates at most one edit farther away than the
+    /// suggestion.
+    pub rivals: usize,
+}
+
+impl SuggestionFeatures {
+    fn inputs(&self) -> [f64; 3] {
+        let distance = self.distance as f64;
+        [
+            distance,
+            distance / cmp::max(self.lookup_len, 1) as f64,
+            self.rivals as f64,
+        ]
+    }
+}
+
+/// A logistic model mapping [`SuggestionFeatures`] to a probability.
+///
+/// The default coefficients are hand-tuned for identifier typos; use
+/// [`Calibration::fit`] to calibrate against labelled data from your own
+/// domain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    /// The log-odds of an exact match with no rivals.
+    pub intercept: f64,
+    /// The weight of the raw edit distance.
+    pub distance_weight: f64,
+    /// The weight of the distance relative to the lookup's length.
+    pub relative_distance_weight: f64,
+    /// The weight of the number of rivals.
+    pub rivals_weight: f64,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Calibration {
+            intercept: 4.0,
+            distance_weight: -1.0,
+            relative_distance_weight: -6.0,
+            rivals_weight: -0.7,
+        }
+    }
+}
+
+impl Calibration {
+    fn weights(&self) -> [f64; 3] {
+        [
+            self.distance_weight,
+            self.relative_distance_weight,
+            self.rivals_weight,
+        ]
+    }
+
+    /// Estimates the probability that a suggestion with the given features
+    /// is what the user meant.
+    pub fn probability(&self, features: SuggestionFeatures) -> f64 {
+        let z = self.intercept
+            + self
+                .weights()
+                .iter()
+                .zip(features.inputs())
+                .map(|(w, x)| w * x)
+                .sum::<f64>();
+        1.0 / (1.0 + (-z).exp())
+    }
+
+    /// Fits a calibration to samples labelled with whether the suggestion was
+    /// accepted, by gradient descent on the log loss.
+    pub fn fit(samples: &[(SuggestionFeatures, bool)]) -> Self {
+        const EPOCHS: usize = 2000;
+        const LEARNING_RATE: f64 = 0.1;
+
+        let mut calibration = Calibration::default();
+        if samples.is_empty() {
+            return calibration;
+        }
+        let n = samples.len() as f64;
+        for _ in 0..EPOCHS {
+            let mut grad_intercept = 0.0;
+            let mut grad = [0.0; 3];
+            for &(features, accepted) in samples {
+                let err = calibration.probability(features) - if accepted { 1.0 } else { 0.0 };
+                grad_intercept += err;
+                for (g, x) in grad.iter_mut().zip(features.inputs()) {
+                    *g += err * x;
+                }
+            }
+            calibration.intercept -= LEARNING_RATE * grad_intercept / n;
+            calibration.distance_weight -= LEARNING_RATE * grad[0] / n;
+            calibration.relative_distance_weight -= LEARNING_RATE * grad[1] / n;
+            calibration.rivals_weight -= LEARNING_RATE * grad[2] / n;
+        }
+        calibration
+    }
+}
+
+/// Finds the best match for `lookup` and estimates how likely it is to be
+/// what the user meant, using the default [`Calibration`].
+///
+/// `dist` is the maximum allowable edit distance as in
+/// [`find_best_match_for_name`](crate::find_best_match_for_name).
+pub fn suggestion_confidence<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<(String, f64)>
+where
+    T: AsRef<str>,
+{
+    let names: Vec<T> = iter_names.collect();
+    let pipeline = Pipeline {
+        threshold: Threshold::from(dist),
+        ..Pipeline::default()
+    };
+    let indexed = names.iter().enumerate().map(|(i, name)| Indexed(i, name));
+    let (Indexed(best, _), distance) =
+        pipeline.find(indexed, lookup, &mut QueryContext::default())?;
+    let limit = distance.get().saturating_add(1);
+    let rivals = names
+        .iter()
+        .enumerate()
+        .filter(|&(i, name)| {
+            i != best && kernel::distance(lookup, name.as_ref(), Some(limit)) <= limit
+        })
+        .count();
+    let features = SuggestionFeatures {
+        distance: distance.get(),
+        lookup_len: lookup.chars().count(),
+        rivals,
+    };
+    Some((
+        names[best].as_ref().to_string(),
+        Calibration::default().probability(features),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probability() {
+        let calibration = Calibration::default();
+        let p = |distance, lookup_len, rivals| {
+            calibration.probability(SuggestionFeatures {
+                distance,
+                lookup_len,
+                rivals,
+            })
+        };
+        assert!(p(0, 5, 0) > 0.95);
+        assert!(p(1, 10, 0) > p(1, 3, 0));
+        assert!(p(1, 10, 0) > p(2, 10, 0));
+        assert!(p(1, 10, 0) > p(1, 10, 3));
+    }
+
+    #[test]
+    fn test_suggestion_confidence() {
+        let (best, lone) = suggestion_confidence(["configure"].iter(), "confgure", None).unwrap();
+        assert_eq!(best, "configure");
+        let (_, crowded) =
+            suggestion_confidence(["configure", "configured"].iter(), "confgure", None).unwrap();
+        assert!(lone > crowded);
+        assert_eq!(suggestion_confidence(["abc"].iter(), "xyz", None), None);
+
+        // The other tiers of the pipeline apply as well.
+        let (best, _) = suggestion_confidence(["a_b", "zzz"].iter(), "b_a", None).unwrap();
+        assert_eq!(best, "a_b");
+        let (best, _) =
+            suggestion_confidence(["PRINTLN_NOW"].iter(), "println_now", Some(0)).unwrap();
+        assert_eq!(best, "PRINTLN_NOW");
+        assert!(suggestion_confidence(["abc"].iter(), "abd", Some(usize::MAX)).is_some());
+    }
+
+    #[test]
+    fn test_fit() {
+        let features = |distance| SuggestionFeatures {
+            distance,
+            lookup_len: 6,
+            rivals: 0,
+        };
+        let samples: Vec<_> = (0..20).map(|i| (features(i % 4), i % 4 < 2)).collect();
+        let calibration = Calibration::fit(&samples);
+        assert!(calibration.probability(features(0)) > 0.5);
+        assert!(calibration.probability(features(3)) < 0.5);
+    }
+}
@@ -0,0 +1,125 @@
+//! Suggestions for configuration keys.
+//!
+//! Config keys are dotted paths like `server.read-timeout`. They are compared
+//! case-insensitively, with `-` and `_` treated as the same separator, and
+//! component by component when both paths have the same depth so that a typo
+//! in one section name doesn't get diluted by the rest of the path.
+
+use std::cmp;
+
+use crate::lev_distance;
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .map(|c| if c == '-' { '_' } else { c })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn path_distance(a: &str, b: &str) -> usize {
+    if a.split('.').count() == b.split('.').count() {
+        a.split('.')
+            .zip(b.split('.'))
+            .map(|(a, b)| lev_distance(a, b))
+            .sum()
+    } else {
+        lev_distance(a, b)
+    }
+}
+
+/// The distance between `unknown` and `key`, and whether it is only that
+/// close to the trailing components of `key`, as deep as `unknown`, e.g. a
+/// bare `timeout` to `server.request.timeout`.
+fn key_distance(unknown: &str, key: &str) -> (usize, bool) {
+    let whole = path_distance(unknown, key);
+    let depth = unknown.split('.').count();
+    let suffix = key
+        .rmatch_indices('.')
+        .nth(depth - 1)
+        .map(|(i, _)| path_distance(unknown, &key[i + 1..]));
+    match suffix {
+        Some(dist) if dist < whole => (dist, true),
+        _ => (whole, false),
+    }
+}
+
+/// Finds the schema key closest to an unknown config key.
+///
+/// `unknown` is also matched against the trailing components of deeper keys,
+/// so a key given without its section still finds it, but a key matching as
+/// a whole wins at the same distance. Returns the full key path as it
+/// appears in `schema_keys`. Keys farther away than one-third of the length
+/// of `unknown` are not suggested.
+pub fn suggest_key<T>(unknown: &str, schema_keys: impl Iterator<Item = T>) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    let unknown = normalize(unknown);
+    let max_dist = cmp::max(unknown.chars().count(), 3) / 3;
+
+    let mut best: Option<(T, (usize, bool))> = None;
+    for key in schema_keys {
+        let rank = key_distance(&unknown, &normalize(key.as_ref()));
+        if rank == (0, false) {
+            return Some(key.as_ref().to_string());
+        }
+        if rank.0 <= max_dist && best.as_ref().is_none_or(|&(_, r)| rank < r) {
+            best = Some((key, rank));
+        }
+    }
+    best.map(|(key, _)| key.as_ref().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_key() {
+        let schema = ["server.port", "server.read_timeout", "log.level"];
+        assert_eq!(
+            suggest_key("Server.Read-Timeout", schema.iter()),
+            Some("server.read_timeout".to_string())
+        );
+        assert_eq!(
+            suggest_key("sever.port", schema.iter()),
+            Some("server.port".to_string())
+        );
+        assert_eq!(
+            suggest_key("server.read_timout", schema.iter()),
+            Some("server.read_timeout".to_string())
+        );
+        assert_eq!(suggest_key("database.url", schema.iter()), None);
+    }
+
+    #[test]
+    fn test_suggest_key_by_component() {
+        // Each component is off by one, which the whole-string heuristic
+        // would also accept, but `log.port` must not win over `log.level`.
+        let schema = ["log.port", "log.level"];
+        assert_eq!(
+            suggest_key("lg.levl", schema.iter()),
+            Some("log.level".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggest_key_by_suffix() {
+        let schema = ["server.request.timeout", "server.port", "log.level"];
+        assert_eq!(
+            suggest_key("timeout", schema.iter()),
+            Some("server.request.timeout".to_string())
+        );
+        assert_eq!(
+            suggest_key("request.timout", schema.iter()),
+            Some("server.request.timeout".to_string())
+        );
+        assert_eq!(
+            suggest_key("Levl", schema.iter()),
+            Some("log.level".to_string())
+        );
+        // A whole key wins over a suffix at the same distance.
+        let schema = ["server.port", "port"];
+        assert_eq!(suggest_key("port", schema.iter()), Some("port".to_string()));
+    }
+}
@@ -0,0 +1,91 @@
+//! Levenshtein distance usable in const contexts.
+
+/// The longest input, in bytes, accepted by [`const_lev_distance`].
+pub const CONST_MAX_LEN: usize = 64;
+
+/// Finds the Levenshtein distance between two strings at compile time.
+///
+/// The distance is computed over bytes rather than `char`s, so it only agrees
+/// with [`lev_distance`](crate::lev_distance) on ASCII input. This lets const
+/// items guard against confusable names:
+///
+/// ```
+/// use lev_distance::const_lev_distance;
+///
+/// const _: () = assert!(const_lev_distance("timeout", "retries") > 2);
+/// ```
+///
+/// # Panics
+///
+/// Panics (failing compilation in a const context) if `b` is longer than
+/// [`CONST_MAX_LEN`] bytes.
+pub const fn const_lev_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    assert!(
+        b.len() <= CONST_MAX_LEN,
+        "const_lev_distance: input longer than CONST_MAX_LEN"
+    );
+
+    let mut row = [0; CONST_MAX_LEN + 1];
+    let mut j = 0;
+    while j <= b.len() {
+        row[j] = j;
+        j += 1;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        let mut j = 0;
+        while j < b.len() {
+            let above = row[j + 1];
+            row[j + 1] = if a[i] == b[j] {
+                diagonal
+            } else {
+                let mut min = diagonal;
+                if above < min {
+                    min = above;
+                }
+                if row[j] < min {
+                    min = row[j];
+                }
+                min + 1
+            };
+            diagonal = above;
+            j += 1;
+        }
+        i += 1;
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    const KITTEN: usize = const_lev_distance("kitten", "sitting");
+
+    #[test]
+    fn test_const_lev_distance() {
+        assert_eq!(KITTEN, 3);
+        for (a, b) in [
+            ("", ""),
+            ("", "abc"),
+            ("abc", ""),
+            ("flaw", "lawn"),
+            ("abc", "abc"),
+        ] {
+            assert_eq!(const_lev_distance(a, b), lev_distance(a, b));
+        }
+        // Non-ASCII characters count once per byte.
+        assert_eq!(const_lev_distance("ä", "a"), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_const_lev_distance_too_long() {
+        const_lev_distance("a", &"b".repeat(CONST_MAX_LEN + 1));
+    }
+}
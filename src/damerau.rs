@@ -0,0 +1,74 @@
+//! The Damerau–Levenshtein distance.
+
+use std::cmp;
+
+use crate::QueryStats;
+
+/// Finds the Damerau–Levenshtein distance between two strings, which also
+/// counts swapping two adjacent chars as a single edit.
+///
+/// This is the restricted variant, also known as the optimal string
+/// alignment distance: no substring is edited more than once.
+pub fn damerau_lev_distance(a: &str, b: &str) -> usize {
+    distance_counted(a, b, None, &mut QueryStats::default())
+}
+
+/// Like [`damerau_lev_distance`], returning some value greater than `limit`
+/// if the distance obviously exceeds it, and recording the work done in
+/// `stats`.
+pub(crate) fn distance_counted(
+    a: &str,
+    b: &str,
+    limit: Option<usize>,
+    stats: &mut QueryStats,
+) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    match limit {
+        Some(limit) if a.len().abs_diff(b.len()) > limit => {
+            stats.early_exits += 1;
+            limit + 1
+        }
+        _ => {
+            stats.cells_computed += a.len() * b.len();
+            osa(&a, &b)
+        }
+    }
+}
+
+fn osa(a: &[char], b: &[char]) -> usize {
+    // The rows for the previous two chars of `a` and the current one.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr: Vec<usize> = vec![0; b.len() + 1];
+    for (i, &ac) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, &bc) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ac != bc);
+            let mut best = cmp::min(substitution, cmp::min(prev[j + 1], curr[j]) + 1);
+            if i > 0 && j > 0 && ac == b[j - 1] && a[i - 1] == bc {
+                best = cmp::min(best, before[j - 1] + 1);
+            }
+            curr[j + 1] = best;
+        }
+        std::mem::swap(&mut before, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_damerau_lev_distance() {
+        assert_eq!(damerau_lev_distance("teh", "the"), 1);
+        assert_eq!(damerau_lev_distance("kitten", "sitting"), 3);
+        assert_eq!(damerau_lev_distance("", "abc"), 3);
+        assert_eq!(damerau_lev_distance("abc", ""), 3);
+        // The restricted variant can't edit the swapped pair again.
+        assert_eq!(damerau_lev_distance("ca", "abc"), 3);
+        assert_eq!(damerau_lev_distance("größe", "gröeß"), 1);
+    }
+}
@@ -0,0 +1,145 @@
+//! A SymSpell-style index of deletion variants.
+//!
+//! Two words are within `k` edits only if deleting at most `k` chars from
+//! each can make them equal. Indexing every word under all of its variants
+//! with up to `k` chars deleted turns a query into a handful of hash
+//! lookups for its own variants, followed by verifying the few words found.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{kernel, Distance};
+
+/// An index answering queries within a fixed maximum distance.
+#[derive(Clone, Debug)]
+pub struct DeletionIndex {
+    max_dist: usize,
+    words: Vec<String>,
+    /// The indices in `words` of the words having each variant.
+    variants: HashMap<String, Vec<usize>>,
+}
+
+impl DeletionIndex {
+    /// Creates an empty index for queries within `max_dist`.
+    ///
+    /// The index grows quickly with `max_dist`; 1 or 2 is typical.
+    pub fn new(max_dist: usize) -> Self {
+        DeletionIndex {
+            max_dist,
+            words: Vec::new(),
+            variants: HashMap::new(),
+        }
+    }
+
+    /// The number of words in the index.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the index holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Inserts `word`, returning whether it was not already in the index.
+    pub fn insert(&mut self, word: impl Into<String>) -> bool {
+        let word = word.into();
+        let exists = self
+            .variants
+            .get(&word)
+            .is_some_and(|indices| indices.iter().any(|&i| self.words[i] == word));
+        if exists {
+            return false;
+        }
+        let index = self.words.len();
+        for variant in deletion_variants(&word, self.max_dist) {
+            self.variants.entry(variant).or_default().push(index);
+        }
+        self.words.push(word);
+        true
+    }
+
+    /// Finds every word within `max_dist` of `query`, closest first; words at
+    /// the same distance keep their insertion order.
+    ///
+    /// `max_dist` is capped at the maximum the index was created for.
+    pub fn find_within(&self, query: &str, max_dist: usize) -> Vec<(&str, Distance)> {
+        let max_dist = max_dist.min(self.max_dist);
+        let mut seen = HashSet::new();
+        let mut found: Vec<(usize, usize)> = Vec::new();
+        for variant in deletion_variants(query, max_dist) {
+            for &index in self.variants.get(&variant).into_iter().flatten() {
+                if !seen.insert(index) {
+                    continue;
+                }
+                let dist = kernel::distance(query, &self.words[index], Some(max_dist));
+                if dist <= max_dist {
+                    found.push((dist, index));
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|(dist, index)| (self.words[index].as_str(), Distance(dist)))
+            .collect()
+    }
+}
+
+impl<S: Into<String>> Extend<S> for DeletionIndex {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        for word in words {
+            self.insert(word);
+        }
+    }
+}
+
+/// All distinct strings made by deleting up to `max` chars from `word`,
+/// including `word` itself.
+fn deletion_variants(word: &str, max: usize) -> HashSet<String> {
+    let mut variants = HashSet::from([word.to_string()]);
+    let mut frontier = vec![word.to_string()];
+    for _ in 0..max {
+        let mut next = Vec::new();
+        for s in &frontier {
+            for (i, c) in s.char_indices() {
+                let variant = format!("{}{}", &s[..i], &s[i + c.len_utf8()..]);
+                if variants.insert(variant.clone()) {
+                    next.push(variant);
+                }
+            }
+        }
+        frontier = next;
+    }
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::check_find_within;
+
+    const WORDS: &[&str] = &[
+        "the", "they", "then", "their", "there", "hello", "help", "über", "uber", "a", "",
+    ];
+
+    #[test]
+    fn test_find_within() {
+        let mut index = DeletionIndex::new(2);
+        index.extend(WORDS.iter().copied());
+        assert_eq!(index.len(), WORDS.len());
+        assert!(!index.insert("the"));
+        let queries = ["teh", "ther", "helo", "ubr", "", "zzzzzz"];
+        check_find_within(WORDS, &queries, 2, |query, max_dist| {
+            index.find_within(query, max_dist)
+        });
+        // Capped at the maximum of the index.
+        assert_eq!(index.find_within("thexxx", 5), vec![]);
+    }
+
+    #[test]
+    fn test_deletion_variants() {
+        let mut variants: Vec<String> = deletion_variants("abb", 1).into_iter().collect();
+        variants.sort();
+        assert_eq!(variants, ["ab", "abb", "bb"]);
+    }
+}
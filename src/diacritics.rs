@@ -0,0 +1,65 @@
+//! Diacritic-insensitive comparison.
+
+/// The base letter of a lowercase Latin letter with diacritics.
+fn base_letter(c: char) -> Option<char> {
+    Some(match c {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => return None,
+    })
+}
+
+/// Whether `c` is a combining diacritical mark.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36f}')
+}
+
+/// Removes the diacritics from the Latin letters of `s`, both precomposed
+/// and combining, so that "résumé" becomes "resume".
+///
+/// Case is preserved. Other scripts are left untouched.
+pub fn strip_diacritics(s: &str) -> String {
+    s.chars()
+        .filter(|&c| !is_combining_mark(c))
+        .map(|c| {
+            if let Some(base) = base_letter(c) {
+                return base;
+            }
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            match base_letter(lower) {
+                Some(base) if lower != c => base.to_ascii_uppercase(),
+                _ => c,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(strip_diacritics("résumé"), "resume");
+        assert_eq!(strip_diacritics("re\u{301}sume\u{301}"), "resume");
+        assert_eq!(strip_diacritics("ÅNGSTRÖM Łódź"), "ANGSTROM Lodz");
+        assert_eq!(strip_diacritics("Straße ΟΔΟΣ"), "Straße ΟΔΟΣ");
+    }
+}
@@ -0,0 +1,181 @@
+//! Ratcliff/Obershelp gestalt pattern matching, as in Python's `difflib`.
+
+use std::cmp::{self, Ordering};
+use std::collections::HashMap;
+
+/// The similarity [`get_close_matches`] requires by default, as in Python.
+pub const DEFAULT_GESTALT_CUTOFF: f64 = 0.6;
+
+/// Finds the Ratcliff/Obershelp similarity of two strings in `0.0..=1.0`,
+/// as returned by `difflib.SequenceMatcher(None, a, b).ratio()` in Python.
+///
+/// The longest common substring is matched first, then the same is done
+/// recursively to its left and right; the similarity is twice the matched
+/// chars over the total. Two empty strings have a similarity of `1.0`.
+/// Unlike Python, no chars are ever treated as junk.
+pub fn gestalt_ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    ratio(matched_chars(&a, &b), a.len() + b.len())
+}
+
+fn ratio(matches: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * matches as f64 / total as f64
+}
+
+/// The number of chars in the matching blocks of `a` and `b`.
+fn matched_chars(a: &[char], b: &[char]) -> usize {
+    let mut rows = (vec![0; b.len() + 1], vec![0; b.len() + 1]);
+    let mut matches = 0;
+    let mut queue = vec![(0, a.len(), 0, b.len())];
+    while let Some((alo, ahi, blo, bhi)) = queue.pop() {
+        let (i, j, k) = longest_match(a, b, (alo, ahi), (blo, bhi), &mut rows);
+        if k == 0 {
+            continue;
+        }
+        matches += k;
+        if alo < i && blo < j {
+            queue.push((alo, i, blo, j));
+        }
+        if i + k < ahi && j + k < bhi {
+            queue.push((i + k, ahi, j + k, bhi));
+        }
+    }
+    matches
+}
+
+/// The longest common substring of `a[alo..ahi]` and `b[blo..bhi]` as
+/// `(i, j, len)`, the earliest in `a` and then in `b` on ties, as in Python.
+///
+/// `rows` are scratch space of at least `bhi - blo + 1` cells each.
+fn longest_match(
+    a: &[char],
+    b: &[char],
+    (alo, ahi): (usize, usize),
+    (blo, bhi): (usize, usize),
+    (prev, row): &mut (Vec<usize>, Vec<usize>),
+) -> (usize, usize, usize) {
+    let width = bhi - blo + 1;
+    prev[..width].fill(0);
+    row[0] = 0;
+    let mut best = (alo, blo, 0);
+    for (i, ac) in (alo..).zip(&a[alo..ahi]) {
+        for (cell, bc) in (1..).zip(&b[blo..bhi]) {
+            row[cell] = if ac == bc { prev[cell - 1] + 1 } else { 0 };
+            if row[cell] > best.2 {
+                best = (i + 1 - row[cell], blo + cell - row[cell], row[cell]);
+            }
+        }
+        std::mem::swap(prev, row);
+    }
+    best
+}
+
+/// An upper bound on [`gestalt_ratio`] from the chars both strings have,
+/// regardless of their order.
+fn quick_ratio(a: &[char], b: &[char]) -> f64 {
+    let mut counts: HashMap<char, isize> = HashMap::new();
+    for &c in b {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let mut matches = 0;
+    for c in a {
+        if let Some(count) = counts.get_mut(c) {
+            if *count > 0 {
+                matches += 1;
+            }
+            *count -= 1;
+        }
+    }
+    ratio(matches, a.len() + b.len())
+}
+
+/// Finds the at most `n` best matches for `word` in `possibilities` by
+/// [`gestalt_ratio`], best first, like Python's
+/// `difflib.get_close_matches`.
+///
+/// Possibilities need a similarity of at least `cutoff`, which defaults to
+/// [`DEFAULT_GESTALT_CUTOFF`]. As in Python, ties go to the possibility
+/// that sorts last.
+pub fn get_close_matches<T>(
+    word: &str,
+    possibilities: impl Iterator<Item = T>,
+    n: usize,
+    cutoff: Option<f64>,
+) -> Vec<String>
+where
+    T: AsRef<str>,
+{
+    let cutoff = cutoff.unwrap_or(DEFAULT_GESTALT_CUTOFF);
+    let word: Vec<char> = word.chars().collect();
+    let mut matches: Vec<(f64, T)> = possibilities
+        .filter_map(|possibility| {
+            let chars: Vec<char> = possibility.as_ref().chars().collect();
+            let total = chars.len() + word.len();
+            // Cheap upper bounds first, as in Python.
+            if ratio(cmp::min(chars.len(), word.len()), total) < cutoff
+                || quick_ratio(&chars, &word) < cutoff
+            {
+                return None;
+            }
+            let score = ratio(matched_chars(&chars, &word), total);
+            (score >= cutoff).then_some((score, possibility))
+        })
+        .collect();
+    matches.sort_by(|(x, a), (y, b)| {
+        y.partial_cmp(x)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.as_ref().cmp(a.as_ref()))
+    });
+    matches
+        .into_iter()
+        .take(n)
+        .map(|(_, possibility)| possibility.as_ref().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gestalt_ratio() {
+        assert_eq!(gestalt_ratio("abcd", "bcde"), 0.75);
+        assert_eq!(gestalt_ratio("", ""), 1.0);
+        assert_eq!(gestalt_ratio("abc", ""), 0.0);
+        // "wikim" and then "ia"
+        assert_eq!(gestalt_ratio("wikimedia", "wikimania"), 14.0 / 18.0);
+        assert_eq!(
+            gestalt_ratio("GESTALT PATTERN MATCHING", "GESTALT PRACTICE"),
+            0.6
+        );
+    }
+
+    #[test]
+    fn test_get_close_matches() {
+        let words = ["ape", "apple", "peach", "puppy"];
+        assert_eq!(
+            get_close_matches("appel", words.iter(), 3, None),
+            ["apple", "ape"]
+        );
+        let keywords = ["while", "except", "for", "if", "else", "import"];
+        assert_eq!(
+            get_close_matches("wheel", keywords.iter(), 3, None),
+            ["while"]
+        );
+        assert_eq!(
+            get_close_matches("accept", keywords.iter(), 3, None),
+            ["except"]
+        );
+        assert!(get_close_matches("appel", words.iter(), 0, None).is_empty());
+        assert!(get_close_matches("appel", words.iter(), 3, Some(0.9)).is_empty());
+        // Ties go to the one that sorts last.
+        assert_eq!(
+            get_close_matches("abc", ["abx", "aby"].iter(), 1, None),
+            ["aby"]
+        );
+    }
+}
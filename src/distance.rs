@@ -0,0 +1,76 @@
+//! A typed edit distance.
+
+use std::fmt;
+
+use crate::kernel;
+
+/// An edit distance, kept apart from lengths and indices by its type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Distance(pub usize);
+
+impl Distance {
+    /// The distance between equal strings.
+    pub const ZERO: Distance = Distance(0);
+
+    /// Finds the Levenshtein distance between two strings.
+    pub fn between(a: &str, b: &str) -> Distance {
+        Distance(kernel::distance(a, b, None))
+    }
+
+    /// The number of edits.
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    /// The distance relative to `len`, typically the length of the longer
+    /// string, where `0.0` means equal.
+    ///
+    /// A `len` of zero yields `0.0`.
+    pub fn normalized(self, len: usize) -> f64 {
+        if len == 0 {
+            0.0
+        } else {
+            self.0 as f64 / len as f64
+        }
+    }
+
+    /// Whether the distance is at most `k` edits.
+    pub fn within(self, k: usize) -> bool {
+        self.0 <= k
+    }
+}
+
+impl From<Distance> for usize {
+    fn from(distance: Distance) -> usize {
+        distance.0
+    }
+}
+
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let d = Distance::between("kitten", "sitting");
+        assert_eq!(d, Distance(3));
+        assert_eq!(d.get(), 3);
+        assert_eq!(usize::from(d), 3);
+        assert!(d.within(3));
+        assert!(!d.within(2));
+        assert!(Distance::ZERO < d);
+        assert_eq!(d.to_string(), "3");
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(Distance(3).normalized(6), 0.5);
+        assert_eq!(Distance(0).normalized(0), 0.0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Detection of near-duplicate entries within a candidate set.
+//!
+//! Candidate sets that contain both `time_out` and `timeout` make every
+//! suggestion between them a coin flip; these helpers find such entries so
+//! they can be linted away.
+
+use crate::kernel;
+
+/// Two entries that are suspiciously similar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NearDuplicate {
+    /// The index of the earlier entry.
+    pub first: usize,
+    /// The index of the later entry.
+    pub second: usize,
+    /// The edit distance between the two.
+    pub distance: usize,
+}
+
+/// Finds all pairs of entries at most `max_dist` edits apart.
+///
+/// Pairs are ordered by `first`, then `second`. This compares every pair of
+/// entries, so it is quadratic in the number of items.
+pub fn find_near_duplicates<T>(items: &[T], max_dist: usize) -> Vec<NearDuplicate>
+where
+    T: AsRef<str>,
+{
+    let mut pairs = Vec::new();
+    for (first, a) in items.iter().enumerate() {
+        for (second, b) in items.iter().enumerate().skip(first + 1) {
+            let distance = kernel::distance(a.as_ref(), b.as_ref(), Some(max_dist));
+            if distance <= max_dist {
+                pairs.push(NearDuplicate {
+                    first,
+                    second,
+                    distance,
+                });
+            }
+        }
+    }
+    pairs
+}
+
+/// Groups entries that are connected by chains of near duplicates.
+///
+/// Returns the indices of each group with more than one entry, in ascending
+/// order. Entries without near duplicates are left out.
+pub fn group_near_duplicates<T>(items: &[T], max_dist: usize) -> Vec<Vec<usize>>
+where
+    T: AsRef<str>,
+{
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut parent: Vec<usize> = (0..items.len()).collect();
+    for pair in find_near_duplicates(items, max_dist) {
+        let (a, b) = (
+            root(&mut parent, pair.first),
+            root(&mut parent, pair.second),
+        );
+        parent[a.max(b)] = a.min(b);
+    }
+
+    let mut groups: Vec<Vec<usize>> = vec![Vec::new(); items.len()];
+    for i in 0..items.len() {
+        let r = root(&mut parent, i);
+        groups[r].push(i);
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_near_duplicates() {
+        let items = ["timeout", "retries", "time_out", "retry"];
+        assert_eq!(
+            find_near_duplicates(&items, 1),
+            vec![NearDuplicate {
+                first: 0,
+                second: 2,
+                distance: 1
+            }]
+        );
+        assert_eq!(find_near_duplicates(&items, 3).len(), 2);
+        assert!(find_near_duplicates(&items, 0).is_empty());
+    }
+
+    #[test]
+    fn test_group_near_duplicates() {
+        let items = ["color", "colour", "size", "colours", "sizes", "weight"];
+        assert_eq!(
+            group_near_duplicates(&items, 1),
+            vec![vec![0, 1, 3], vec![2, 4]]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! The edits behind a Levenshtein distance.
+
+use std::cmp;
+
+/// A single edit turning one string into another.
+///
+/// Positions count chars, not bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditOp {
+    /// Inserts `b[b_pos]` before `a[a_pos]`.
+    Insert { a_pos: usize, b_pos: usize },
+    /// Deletes `a[a_pos]`.
+    Delete { a_pos: usize },
+    /// Replaces `a[a_pos]` by `b[b_pos]`.
+    Substitute { a_pos: usize, b_pos: usize },
+}
+
+/// Finds a shortest sequence of edits turning `a` into `b`, in order of
+/// position.
+///
+/// The number of edits is the [`lev_distance`](crate::lev_distance) of the
+/// strings. Unlike the distance, this keeps the whole DP matrix, so it
+/// takes memory proportional to the product of the lengths.
+pub fn lev_edit_ops(a: &str, b: &str) -> Vec<EditOp> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let width = b.len() + 1;
+    let mut d: Vec<usize> = (0..width).collect();
+    d.resize((a.len() + 1) * width, 0);
+    for i in 1..=a.len() {
+        d[i * width] = i;
+        for j in 1..=b.len() {
+            let substitution = d[(i - 1) * width + j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let deletion = d[(i - 1) * width + j] + 1;
+            let insertion = d[i * width + j - 1] + 1;
+            d[i * width + j] = cmp::min(substitution, cmp::min(deletion, insertion));
+        }
+    }
+
+    let mut ops = Vec::with_capacity(d[a.len() * width + b.len()]);
+    let (mut i, mut j) = (a.len(), b.len());
+    while i > 0 || j > 0 {
+        let here = d[i * width + j];
+        if i > 0 && j > 0 && d[(i - 1) * width + j - 1] + usize::from(a[i - 1] != b[j - 1]) == here
+        {
+            if a[i - 1] != b[j - 1] {
+                ops.push(EditOp::Substitute {
+                    a_pos: i - 1,
+                    b_pos: j - 1,
+                });
+            }
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && d[(i - 1) * width + j] + 1 == here {
+            ops.push(EditOp::Delete { a_pos: i - 1 });
+            i -= 1;
+        } else {
+            ops.push(EditOp::Insert {
+                a_pos: i,
+                b_pos: j - 1,
+            });
+            j -= 1;
+        }
+    }
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_lev_edit_ops() {
+        assert_eq!(
+            lev_edit_ops("kitten", "sitting"),
+            vec![
+                EditOp::Substitute { a_pos: 0, b_pos: 0 },
+                EditOp::Substitute { a_pos: 4, b_pos: 4 },
+                EditOp::Insert { a_pos: 6, b_pos: 6 },
+            ]
+        );
+        assert_eq!(
+            lev_edit_ops("größe", "gröe"),
+            vec![EditOp::Delete { a_pos: 3 }]
+        );
+        assert_eq!(lev_edit_ops("same", "same"), vec![]);
+        for (a, b) in [("", "abc"), ("flaw", "lawn"), ("abcabc", "abc")] {
+            assert_eq!(lev_edit_ops(a, b).len(), lev_distance(a, b));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! An embedded list of common English words.
+
+use std::sync::OnceLock;
+
+use crate::table::suggest_ignore_ascii_case;
+
+const ENGLISH_WORDS: &str = include_str!("../data/english_words.txt");
+
+fn words() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| ENGLISH_WORDS.lines().collect())
+}
+
+/// Returns the embedded English words, most frequent first.
+///
+/// The iterator can be passed directly as the candidates of
+/// [`find_best_match_for_name`](crate::find_best_match_for_name).
+pub fn english_words() -> impl Iterator<Item = &'static str> + Clone {
+    words().iter().copied()
+}
+
+/// Suggests the English word closest to `word`, ignoring case.
+///
+/// Among equally close words the more frequent one wins. Returns `None` if
+/// `word` is in the list or nothing is close enough.
+pub fn suggest_word(word: &str) -> Option<&'static str> {
+    suggest_ignore_ascii_case(words(), word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_best_match_for_name;
+
+    #[test]
+    fn test_english_words() {
+        assert_eq!(english_words().next(), Some("the"));
+        assert!(english_words().any(|w| w == "dictionary"));
+        assert_eq!(
+            find_best_match_for_name(english_words(), "dictionray", None),
+            Some("dictionary".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggest_word() {
+        assert_eq!(suggest_word("thw"), Some("the"));
+        assert_eq!(suggest_word("wrld"), Some("world"));
+        assert_eq!(suggest_word("Mountian"), Some("mountain"));
+        assert_eq!(suggest_word("house"), None);
+        assert_eq!(suggest_word("xylophonist"), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! Ranking by how common candidates are as well as by their distance.
+//!
+//! As in Norvig's spelling corrector, the likeliest correction is a common
+//! word a few edits away rather than a rare word that happens to be one edit
+//! closer. Every edit divides a candidate's weight by [`WEIGHT_PER_EDIT`],
+//! so a common word wins ties outright and near-ties if it is common enough.
+
+use crate::static_match::eq_ignore_case;
+use crate::{kernel, Threshold};
+
+/// How many times the weight of a candidate must exceed that of another
+/// for it to make up for one more edit in
+/// [`find_best_match_by_frequency`].
+pub const WEIGHT_PER_EDIT: f64 = 100.0;
+
+/// Finds the best match for a given word among weighted candidates, such as
+/// words with their frequency in a corpus or names with their popularity.
+///
+/// Candidates within the maximum edit distance, which defaults to one-third
+/// of the given word as in
+/// [`find_best_match_for_name`](crate::find_best_match_for_name), are
+/// ranked by their weight divided by [`WEIGHT_PER_EDIT`] once per edit, with
+/// case-insensitive matches at distance zero. Ties go to the closer
+/// candidate, and then to the earliest. An exact match always wins, however
+/// rare. Weights should not be negative.
+pub fn find_best_match_by_frequency<T>(
+    candidates: impl Iterator<Item = (T, f64)>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    let max_dist = Threshold::from(dist).max_dist(lookup);
+    let mut best: Option<(f64, usize, T)> = None;
+    for (candidate, weight) in candidates {
+        let name = candidate.as_ref();
+        if name == lookup {
+            return Some(name.to_string());
+        }
+        let dist = if eq_ignore_case(name, lookup) {
+            0
+        } else {
+            kernel::distance(lookup, name, Some(max_dist))
+        };
+        if dist > max_dist {
+            continue;
+        }
+        let score = weight / WEIGHT_PER_EDIT.powi(dist as i32);
+        if best
+            .as_ref()
+            .is_none_or(|&(s, d, _)| score > s || (score == s && dist < d))
+        {
+            best = Some((score, dist, candidate));
+        }
+    }
+    best.map(|(_, _, candidate)| candidate.as_ref().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_best_match_by_frequency() {
+        let words = [("tech", 2.0), ("the", 50_000.0), ("then", 800.0)];
+        // "tech" is one edit away, but "the" is far more common.
+        assert_eq!(
+            find_best_match_by_frequency(words.iter().copied(), "teh", Some(2)),
+            Some("the".to_string())
+        );
+        assert_eq!(
+            find_best_match_by_frequency(words.iter().copied(), "teh", Some(1)),
+            Some("tech".to_string())
+        );
+        // Equally distant, so the more common word wins.
+        assert_eq!(
+            find_best_match_by_frequency(words.iter().copied(), "thex", None),
+            Some("the".to_string())
+        );
+        assert_eq!(
+            find_best_match_by_frequency(words.iter().copied(), "THEN", None),
+            Some("then".to_string())
+        );
+        let rare = [("cat", 1.0), ("cart", 50.0)];
+        assert_eq!(
+            find_best_match_by_frequency(rare.iter().copied(), "cat", Some(1)),
+            Some("cat".to_string())
+        );
+        assert_eq!(
+            find_best_match_by_frequency(words.iter().copied(), "xyzzy", None),
+            None
+        );
+    }
+}
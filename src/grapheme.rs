@@ -0,0 +1,52 @@
+//! Distances over extended grapheme clusters.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{kernel, QueryStats};
+
+/// Finds the Levenshtein distance between two strings, counting extended
+/// grapheme clusters instead of `char`s as the unit of editing.
+///
+/// A user-perceived character such as "é" written as `e` plus a combining
+/// accent is one edit away from "a" here, rather than two.
+pub fn grapheme_lev_distance(a: &str, b: &str) -> usize {
+    distance_counted(a, b, None, &mut QueryStats::default())
+}
+
+/// Like [`grapheme_lev_distance`], returning some value greater than `limit`
+/// if the distance obviously exceeds it, and recording the work done in
+/// `stats`.
+pub(crate) fn distance_counted(
+    a: &str,
+    b: &str,
+    limit: Option<usize>,
+    stats: &mut QueryStats,
+) -> usize {
+    let a: Vec<&str> = a.graphemes(true).collect();
+    let b: Vec<&str> = b.graphemes(true).collect();
+    match limit {
+        Some(limit) if a.len().abs_diff(b.len()) > limit => {
+            stats.early_exits += 1;
+            limit + 1
+        }
+        _ => {
+            stats.cells_computed += a.len() * b.len();
+            kernel::slice(&a, &b)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_grapheme_lev_distance() {
+        assert_eq!(lev_distance("e\u{301}", "a"), 2);
+        assert_eq!(grapheme_lev_distance("e\u{301}", "a"), 1);
+        assert_eq!(grapheme_lev_distance("cafe\u{301}", "cafe"), 1);
+        assert_eq!(grapheme_lev_distance("🇯🇵🇫🇷", "🇫🇷"), 1);
+        assert_eq!(grapheme_lev_distance("kitten", "sitting"), 3);
+    }
+}
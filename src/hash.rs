@@ -0,0 +1,35 @@
+//! Small deterministic hash functions for fingerprinting.
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(FNV_OFFSET, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The SplitMix64 finalizer, used to derive independent hash functions.
+pub(crate) fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Hashes of the `n`-character n-grams of `s`, or of `s` as a whole if it is
+/// no longer than `n`.
+pub(crate) fn ngram_hashes(s: &str, n: usize) -> Vec<u64> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() <= n {
+        return vec![hash_chars(&chars)];
+    }
+    chars.windows(n).map(hash_chars).collect()
+}
+
+/// Hashes `chars` by their code points, so that a string no longer than an
+/// n-gram hashes the same as an n-gram of the same chars.
+fn hash_chars(chars: &[char]) -> u64 {
+    fnv1a(chars.iter().flat_map(|c| (*c as u32).to_le_bytes()))
+}
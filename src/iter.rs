@@ -0,0 +1,204 @@
+//! Fuzzy matching as iterator adapters.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{kernel, Distance};
+
+/// Levenshtein-based adapters for any iterator over strings.
+pub trait IteratorExt: Iterator {
+    /// Returns the item closest to `lookup` with its distance, or `None` if
+    /// the iterator is empty.
+    ///
+    /// The earliest of equally close items wins. Once an item is found, the
+    /// distances of the rest are only computed as far as they could win.
+    fn min_by_lev_distance(self, lookup: &str) -> Option<(Self::Item, Distance)>
+    where
+        Self: Sized,
+        Self::Item: AsRef<str>,
+    {
+        let mut best: Option<(Self::Item, usize)> = None;
+        for item in self {
+            let limit = match &best {
+                Some((_, 0)) => break,
+                Some((_, dist)) => Some(dist - 1),
+                None => None,
+            };
+            let dist = kernel::distance(lookup, item.as_ref(), limit);
+            if limit.is_none_or(|limit| dist <= limit) {
+                best = Some((item, dist));
+            }
+        }
+        best.map(|(item, dist)| (item, Distance(dist)))
+    }
+
+    /// Yields the items with their distances to `lookup`, closest first.
+    ///
+    /// Equally close items keep their original order. All distances are
+    /// computed up front, but the items are ordered lazily, so taking the
+    /// first few of many items is cheap.
+    fn sorted_by_lev_distance(self, lookup: &str) -> SortedByLevDistance<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: AsRef<str>,
+    {
+        let heap = self
+            .enumerate()
+            .map(|(seq, item)| HeapEntry {
+                dist: kernel::distance(lookup, item.as_ref(), None),
+                seq,
+                item,
+            })
+            .collect();
+        SortedByLevDistance { heap }
+    }
+
+    /// Keeps only the items within `k` edits of `lookup`.
+    ///
+    /// The distance of each item is only computed as far as `k`, and the
+    /// items themselves are yielded, so this composes with the other
+    /// adapters.
+    fn within_lev_distance(self, lookup: &str, k: usize) -> WithinLevDistance<Self>
+    where
+        Self: Sized,
+        Self::Item: AsRef<str>,
+    {
+        WithinLevDistance {
+            iter: self,
+            lookup: lookup.to_string(),
+            k,
+        }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// The iterator returned by [`IteratorExt::sorted_by_lev_distance`].
+#[derive(Clone, Debug)]
+pub struct SortedByLevDistance<T> {
+    heap: BinaryHeap<HeapEntry<T>>,
+}
+
+impl<T> Iterator for SortedByLevDistance<T> {
+    type Item = (T, Distance);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap
+            .pop()
+            .map(|entry| (entry.item, Distance(entry.dist)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for SortedByLevDistance<T> {}
+
+/// The iterator returned by [`IteratorExt::within_lev_distance`].
+#[derive(Clone, Debug)]
+pub struct WithinLevDistance<I> {
+    iter: I,
+    lookup: String,
+    k: usize,
+}
+
+impl<I> Iterator for WithinLevDistance<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (lookup, k) = (&self.lookup, self.k);
+        self.iter
+            .find(|item| kernel::distance(lookup, item.as_ref(), Some(k)) <= k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+/// An item ordered so that the max-heap pops the closest, earliest one.
+#[derive(Clone, Debug)]
+struct HeapEntry<T> {
+    dist: usize,
+    seq: usize,
+    item: T,
+}
+
+impl<T> PartialEq for HeapEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for HeapEntry<T> {}
+
+impl<T> PartialOrd for HeapEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for HeapEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.dist, other.seq).cmp(&(self.dist, self.seq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_by_lev_distance() {
+        let items = ["print", "println", "eprint", "prind"];
+        assert_eq!(
+            items.iter().min_by_lev_distance("prin"),
+            Some((&"print", Distance(1)))
+        );
+        assert_eq!(
+            items
+                .into_iter()
+                .filter(|s| s.len() > 5)
+                .min_by_lev_distance("prin"),
+            Some(("eprint", Distance(2)))
+        );
+        assert_eq!(std::iter::empty::<&str>().min_by_lev_distance("prin"), None);
+    }
+
+    #[test]
+    fn test_sorted_by_lev_distance() {
+        let items = ["println", "print", "format", "eprint", "prind"];
+        let sorted: Vec<_> = items.into_iter().sorted_by_lev_distance("prin").collect();
+        assert_eq!(
+            sorted,
+            vec![
+                ("print", Distance(1)),
+                ("prind", Distance(1)),
+                ("eprint", Distance(2)),
+                ("println", Distance(3)),
+                ("format", Distance(5)),
+            ]
+        );
+        assert_eq!(items.iter().sorted_by_lev_distance("prin").len(), 5);
+    }
+
+    #[test]
+    fn test_within_lev_distance() {
+        let items = ["println", "print", "format", "eprint", "prind"];
+        let within: Vec<_> = items.iter().within_lev_distance("prin", 2).collect();
+        assert_eq!(within, [&"print", &"eprint", &"prind"]);
+        assert_eq!(
+            items
+                .into_iter()
+                .within_lev_distance("prin", 2)
+                .sorted_by_lev_distance("eprin")
+                .next(),
+            Some(("eprint", Distance(1)))
+        );
+    }
+}
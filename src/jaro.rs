@@ -0,0 +1,173 @@
+//! The Jaro and Jaro–Winkler similarities.
+//!
+//! Jaro–Winkler emphasizes shared prefixes, which suits short names and
+//! person names better than edit distances do.
+
+use std::cmp;
+
+use crate::static_match::eq_ignore_case;
+use crate::WordSplit;
+
+/// The similarity [`find_best_match_for_name_jaro_winkler`] requires by
+/// default.
+pub const DEFAULT_MIN_JARO_WINKLER: f64 = 0.8;
+
+/// The Jaro similarity in `0.0..=1.0`, where `1.0` means equal.
+pub fn jaro(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (cmp::max(a.len(), b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, &ac) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = cmp::min(i + window + 1, b.len());
+        for j in lo..hi {
+            if !b_matched[j] && b[j] == ac {
+                b_matched[j] = true;
+                a_matches.push(ac);
+                break;
+            }
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+
+    let b_matches = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, &m)| m)
+        .map(|(&c, _)| c);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(&x, y)| x != *y)
+        .count()
+        / 2;
+    let m = a_matches.len() as f64;
+    (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0
+}
+
+/// The Jaro–Winkler similarity in `0.0..=1.0`, which boosts the Jaro
+/// similarity of strings sharing a prefix of up to four chars.
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let jaro = jaro(a, b);
+    let prefix = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .take(4)
+        .count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// Finds the best match for a given word in the given iterator, ranking by
+/// [`jaro_winkler`] instead of the Levenshtein distance.
+///
+/// Candidates need a similarity of at least `min_similarity`, which defaults
+/// to [`DEFAULT_MIN_JARO_WINKLER`]; the most similar one wins, the earliest
+/// on ties. Exact case-insensitive and sorted-word matches are found as in
+/// [`find_best_match_for_name`](crate::find_best_match_for_name).
+pub fn find_best_match_for_name_jaro_winkler<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    min_similarity: Option<f64>,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    let min_similarity = min_similarity.unwrap_or(DEFAULT_MIN_JARO_WINKLER);
+    let split = WordSplit::new();
+    let mut exact_match = None;
+    let mut jaro_winkler_match: Option<(f64, T)> = None;
+    let mut sorted_words_match = None;
+    for candidate in iter_names {
+        let name = candidate.as_ref();
+        if name == lookup {
+            return Some(name.to_string());
+        } else if exact_match.is_some() {
+            continue;
+        } else if eq_ignore_case(name, lookup) {
+            exact_match = Some(candidate);
+            continue;
+        }
+        let similarity = jaro_winkler(name, lookup);
+        if similarity >= min_similarity {
+            if jaro_winkler_match
+                .as_ref()
+                .is_none_or(|&(s, _)| similarity > s)
+            {
+                jaro_winkler_match = Some((similarity, candidate));
+            }
+        } else if jaro_winkler_match.is_none() && split.same_words(name, lookup) {
+            sorted_words_match = Some(candidate);
+        }
+    }
+    exact_match
+        .or(jaro_winkler_match.map(|(_, candidate)| candidate))
+        .or(sorted_words_match)
+        .map(|candidate| candidate.as_ref().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        assert!(approx(jaro("MARTHA", "MARHTA"), 0.944));
+        assert!(approx(jaro_winkler("MARTHA", "MARHTA"), 0.961));
+        assert!(approx(jaro_winkler("DIXON", "DICKSONX"), 0.813));
+        assert_eq!(jaro_winkler("", ""), 1.0);
+        assert_eq!(jaro_winkler("abc", ""), 0.0);
+        assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_jaro_winkler() {
+        let input = ["Jonathan", "Johnson", "Jon"];
+        // Levenshtein is too strict for these.
+        assert_eq!(
+            crate::find_best_match_for_name(input.iter(), "Jonathon", Some(0)),
+            None
+        );
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(input.iter(), "Jonathon", None),
+            Some("Jonathan".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(input.iter(), "JON", None),
+            Some("Jon".to_string())
+        );
+        // Too far apart for Levenshtein, but sharing a long prefix.
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(input.iter(), "Johnsonville", None),
+            Some("Johnson".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(input.iter(), "Mary", None),
+            None
+        );
+        // An exact match wins over an earlier case-insensitive one.
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(["PRINT", "print"].iter(), "print", None),
+            Some("print".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name_jaro_winkler(["a_b", "x"].iter(), "b_a", None),
+            Some("a_b".to_string())
+        );
+    }
+}
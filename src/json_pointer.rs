@@ -0,0 +1,109 @@
+//! Fuzzy resolution of JSON Pointers ([RFC 6901]).
+//!
+//! [RFC 6901]: https://datatracker.ietf.org/doc/html/rfc6901
+
+use serde_json::Value;
+
+use crate::find_best_match_for_name;
+
+/// A corrected JSON Pointer for a pointer that didn't resolve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PointerCorrection {
+    /// The corrected pointer, which resolves in the document.
+    pub pointer: String,
+    /// The first component of the original pointer that didn't resolve,
+    /// unescaped.
+    pub failed_component: String,
+    /// The position of `failed_component` among the pointer's components.
+    pub failed_index: usize,
+}
+
+fn unescape(component: &str) -> String {
+    component.replace("~1", "/").replace("~0", "~")
+}
+
+fn escape(component: &str) -> String {
+    component.replace('~', "~0").replace('/', "~1")
+}
+
+/// Suggests the nearest existing pointer for a JSON Pointer that doesn't
+/// resolve in `doc`.
+///
+/// Object keys are matched component by component with
+/// [`find_best_match_for_name`]; array indices must be exact. Returns `None`
+/// if the pointer is malformed, already resolves, or some component has no
+/// close enough match.
+pub fn suggest_json_pointer(doc: &Value, pointer: &str) -> Option<PointerCorrection> {
+    if pointer.is_empty() || !pointer.starts_with('/') {
+        return None;
+    }
+
+    let mut current = doc;
+    let mut corrected = String::new();
+    let mut failed = None;
+    for (i, component) in pointer[1..].split('/').map(unescape).enumerate() {
+        let key = match current {
+            Value::Object(map) if map.contains_key(&component) => component,
+            Value::Object(map) => {
+                let key = find_best_match_for_name(map.keys(), &component, None)?;
+                failed.get_or_insert((component, i));
+                key
+            }
+            Value::Array(_) => component,
+            _ => return None,
+        };
+        current = match current {
+            Value::Object(map) => &map[&key],
+            Value::Array(array) => key.parse::<usize>().ok().and_then(|i| array.get(i))?,
+            _ => return None,
+        };
+        corrected.push('/');
+        corrected.push_str(&escape(&key));
+    }
+
+    failed.map(|(failed_component, failed_index)| PointerCorrection {
+        pointer: corrected,
+        failed_component,
+        failed_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_suggest_json_pointer() {
+        let doc = json!({
+            "server": { "ports": [80, 443], "host/name": "example.com" },
+            "logging": { "level": "info" },
+        });
+
+        assert_eq!(
+            suggest_json_pointer(&doc, "/sever/ports/1"),
+            Some(PointerCorrection {
+                pointer: "/server/ports/1".to_string(),
+                failed_component: "sever".to_string(),
+                failed_index: 0,
+            })
+        );
+        assert_eq!(
+            suggest_json_pointer(&doc, "/server/host~1nme"),
+            Some(PointerCorrection {
+                pointer: "/server/host~1name".to_string(),
+                failed_component: "host/nme".to_string(),
+                failed_index: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_suggest_json_pointer_none() {
+        let doc = json!({ "server": { "ports": [80, 443] } });
+        assert_eq!(suggest_json_pointer(&doc, "/server/ports/0"), None);
+        assert_eq!(suggest_json_pointer(&doc, "/server/ports/2"), None);
+        assert_eq!(suggest_json_pointer(&doc, "/database"), None);
+        assert_eq!(suggest_json_pointer(&doc, "server"), None);
+    }
+}
@@ -0,0 +1,464 @@
+//! Distance kernels and the dispatcher choosing between them.
+//!
+//! [`lev_distance`](crate::lev_distance) and the matchers all go through
+//! [`distance`], which picks the cheapest kernel that can handle the inputs:
+//! Myers' bit-parallel algorithm for short strings, a band around the
+//! diagonal for long ones under a limit, and the full DP otherwise, over
+//! bytes if both strings are ASCII and over chars if not. Long strings
+//! under a limit are first put through an approximate q-gram filter, which
+//! only ever rejects pairs that are too far apart, so every kernel stays
+//! exact.
+
+use std::cmp;
+
+use crate::{ngram, QueryStats};
+
+/// Computes the distance between `a` and `b` with whichever kernel suits the
+/// inputs best.
+///
+/// If `limit` is given, the result is exact only when it is at most `limit`;
+/// otherwise some value greater than `limit` is returned, which lets inputs
+/// that are obviously too far apart skip the DP altogether.
+pub(crate) fn distance(a: &str, b: &str, limit: Option<usize>) -> usize {
+    distance_counted(a, b, limit, &mut QueryStats::default())
+}
+
+/// Like [`distance`], recording the work done in `stats`.
+pub(crate) fn distance_counted(
+    a: &str,
+    b: &str,
+    limit: Option<usize>,
+    stats: &mut QueryStats,
+) -> usize {
+    let (a, b) = trim_common_affixes(a, b);
+    if a.is_ascii() && b.is_ascii() {
+        let (x, y) = (a.as_bytes(), b.as_bytes());
+        let (short, long) = if x.len() <= y.len() { (x, y) } else { (y, x) };
+        match limit {
+            Some(limit) if long.len() - short.len() > limit => {
+                stats.early_exits += 1;
+                limit + 1
+            }
+            _ if short.len() <= MYERS_MAX_LEN => {
+                stats.cells_computed += x.len() * y.len();
+                myers_bytes(short, long)
+            }
+            Some(limit) if !ngram::may_be_within(a, b, QGRAM_LEN, limit) => {
+                stats.early_exits += 1;
+                limit + 1
+            }
+            Some(limit) => banded_counted(x, y, limit, stats),
+            None => {
+                stats.cells_computed += x.len() * y.len();
+                slice(x, y)
+            }
+        }
+    } else {
+        let (a_len, b_len) = (a.chars().count(), b.chars().count());
+        let (short, long) = if a_len <= b_len { (a, b) } else { (b, a) };
+        match limit {
+            Some(limit) if a_len.abs_diff(b_len) > limit => {
+                stats.early_exits += 1;
+                limit + 1
+            }
+            _ if cmp::min(a_len, b_len) <= MYERS_MAX_LEN => {
+                stats.cells_computed += a_len * b_len;
+                let short: Vec<char> = short.chars().collect();
+                myers_chars(&short, long.chars())
+            }
+            Some(limit) if !ngram::may_be_within(a, b, QGRAM_LEN, limit) => {
+                stats.early_exits += 1;
+                limit + 1
+            }
+            Some(limit) => {
+                let a: Vec<char> = a.chars().collect();
+                let b: Vec<char> = b.chars().collect();
+                banded_counted(&a, &b, limit, stats)
+            }
+            None => {
+                stats.cells_computed += a_len * b_len;
+                scalar(a, b)
+            }
+        }
+    }
+}
+
+/// The n-gram size of the q-gram filter in front of the banded kernel.
+const QGRAM_LEN: usize = 3;
+
+/// The longest pattern [`myers`] can handle, one bit per element.
+pub(crate) const MYERS_MAX_LEN: usize = 64;
+
+/// Myers' bit-parallel algorithm, in Hyyrö's formulation for the edit
+/// distance between a pattern of `m` elements and a text.
+///
+/// `masks` yields, for every element of the text, the bits of the pattern
+/// positions holding an equal element. A whole DP column is computed per
+/// element of the text, in a handful of word operations.
+pub(crate) fn myers(m: usize, masks: impl Iterator<Item = u64>) -> usize {
+    debug_assert!(m <= MYERS_MAX_LEN);
+    if m == 0 {
+        return masks.count();
+    }
+    let last = 1 << (m - 1);
+    let (mut pv, mut mv) = (!0u64, 0u64);
+    let mut score = m;
+    for eq in masks {
+        let xv = eq | mv;
+        let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+        let ph = mv | !(xh | pv);
+        let mh = pv & xh;
+        if ph & last != 0 {
+            score += 1;
+        } else if mh & last != 0 {
+            score -= 1;
+        }
+        let ph = (ph << 1) | 1;
+        let mh = mh << 1;
+        pv = mh | !(xv | ph);
+        mv = ph & xv;
+    }
+    score
+}
+
+/// Runs [`myers`] with `pattern` as the pattern, which must not be longer
+/// than [`MYERS_MAX_LEN`].
+pub(crate) fn myers_bytes(pattern: &[u8], text: &[u8]) -> usize {
+    let mut peq = [0u64; 256];
+    for (i, &c) in pattern.iter().enumerate() {
+        peq[usize::from(c)] |= 1 << i;
+    }
+    myers(pattern.len(), text.iter().map(|&c| peq[usize::from(c)]))
+}
+
+/// Like [`myers_bytes`], over `char`s.
+pub(crate) fn myers_chars(pattern: &[char], text: impl Iterator<Item = char>) -> usize {
+    let masks = PatternMasks::new(pattern);
+    myers(pattern.len(), text.map(|c| masks.get(c)))
+}
+
+/// The bits of the positions of every distinct char of a pattern, as
+/// consumed by [`myers`].
+pub(crate) struct PatternMasks(Vec<(char, u64)>);
+
+impl PatternMasks {
+    /// Computes the masks of `pattern`, which must not be longer than
+    /// [`MYERS_MAX_LEN`].
+    pub(crate) fn new(pattern: &[char]) -> Self {
+        let mut masks: Vec<(char, u64)> = Vec::with_capacity(pattern.len());
+        for (i, &c) in pattern.iter().enumerate() {
+            match masks.iter_mut().find(|(k, _)| *k == c) {
+                Some((_, mask)) => *mask |= 1 << i,
+                None => masks.push((c, 1 << i)),
+            }
+        }
+        masks.sort_unstable_by_key(|&(c, _)| c);
+        PatternMasks(masks)
+    }
+
+    /// The positions of `c` in the pattern.
+    pub(crate) fn get(&self, c: char) -> u64 {
+        self.0
+            .binary_search_by_key(&c, |&(k, _)| k)
+            .map_or(0, |i| self.0[i].1)
+    }
+}
+
+/// Runs [`banded`], returning `limit + 1` if it gives up and recording the
+/// work done in `stats`.
+fn banded_counted<T: PartialEq>(a: &[T], b: &[T], limit: usize, stats: &mut QueryStats) -> usize {
+    let mut cells = 0;
+    let dist = banded(a, b, limit, &mut cells);
+    stats.cells_computed += cells;
+    dist.unwrap_or_else(|| {
+        stats.early_exits += 1;
+        limit + 1
+    })
+}
+
+/// Strips the prefix and suffix shared by `a` and `b`, which never changes
+/// their distance.
+pub(crate) fn trim_common_affixes<'a, 'b>(a: &'a str, b: &'b str) -> (&'a str, &'b str) {
+    let prefix: usize = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let suffix: usize = a
+        .chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    (&a[..a.len() - suffix], &b[..b.len() - suffix])
+}
+
+/// The DP over `char`s, used for non-ASCII input.
+///
+/// The row is sized and indexed by the number of chars in `b`, never by its
+/// length in bytes.
+pub(crate) fn scalar(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    if b.is_empty() {
+        return a.chars().count();
+    }
+
+    let mut dcol: Vec<usize> = (0..=b.len()).collect();
+    for (i, sc) in a.chars().enumerate() {
+        let mut current = i;
+        dcol[0] = current + 1;
+        for (j, &tc) in b.iter().enumerate() {
+            let next = dcol[j + 1];
+            dcol[j + 1] = if sc == tc {
+                current
+            } else {
+                cmp::min(cmp::min(current, next), dcol[j]) + 1
+            };
+            current = next;
+        }
+    }
+    dcol[b.len()]
+}
+
+/// The DP over arbitrary slices, used for ASCII input as bytes.
+pub(crate) fn slice<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    slice_with_row(a, b, &mut Vec::new())
+}
+
+/// Like [`slice`], with the DP row in `dcol` so that it can be reused.
+pub(crate) fn slice_with_row<T: PartialEq>(a: &[T], b: &[T], dcol: &mut Vec<usize>) -> usize {
+    dcol.clear();
+    dcol.resize(b.len() + 1, 0);
+    slice_in_row(a, b, dcol)
+}
+
+/// Like [`slice`], with the DP row in `dcol`, which must hold exactly
+/// `b.len() + 1` cells.
+pub(crate) fn slice_in_row<T: PartialEq>(a: &[T], b: &[T], dcol: &mut [usize]) -> usize {
+    debug_assert_eq!(dcol.len(), b.len() + 1);
+    if a.is_empty() {
+        return b.len();
+    } else if b.is_empty() {
+        return a.len();
+    }
+
+    for (j, cell) in dcol.iter_mut().enumerate() {
+        *cell = j;
+    }
+    for (i, sc) in a.iter().enumerate() {
+        let mut current = i;
+        dcol[0] = current + 1;
+        for (j, tc) in b.iter().enumerate() {
+            let next = dcol[j + 1];
+            dcol[j + 1] = if sc == tc {
+                current
+            } else {
+                cmp::min(cmp::min(current, next), dcol[j]) + 1
+            };
+            current = next;
+        }
+    }
+    dcol[b.len()]
+}
+
+/// Ukkonen's banded DP: like [`slice`], but only computing the cells within
+/// `limit` of the main diagonal, since any path leaving that band costs more
+/// than `limit`. Gives up with `None` as soon as every cell of a row exceeds
+/// `limit`, since the distance can only grow from there. The number of cells
+/// computed is added to `cells`.
+pub(crate) fn banded<T: PartialEq>(
+    a: &[T],
+    b: &[T],
+    limit: usize,
+    cells: &mut usize,
+) -> Option<usize> {
+    banded_in_row(a, b, limit, cells, &mut vec![0; b.len() + 1])
+}
+
+/// Like [`banded`], with the DP row in `dcol`, which must hold exactly
+/// `b.len() + 1` cells.
+pub(crate) fn banded_in_row<T: PartialEq>(
+    a: &[T],
+    b: &[T],
+    limit: usize,
+    cells: &mut usize,
+    dcol: &mut [usize],
+) -> Option<usize> {
+    debug_assert_eq!(dcol.len(), b.len() + 1);
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+    // Cells outside the band, or above `limit`, are all clamped to `over`.
+    let over = limit.saturating_add(1);
+    for (j, cell) in dcol.iter_mut().enumerate() {
+        *cell = cmp::min(j, over);
+    }
+    for (i, sc) in a.iter().enumerate() {
+        let row = i + 1;
+        let start = cmp::max(1, row.saturating_sub(limit));
+        let end = cmp::min(b.len(), row.saturating_add(limit));
+        *cells += end + 1 - start;
+
+        let mut current = dcol[start - 1];
+        dcol[start - 1] = if start == 1 {
+            cmp::min(row, over)
+        } else {
+            over
+        };
+        let mut row_min = dcol[start - 1];
+        for j in start..=end {
+            let next = dcol[j];
+            dcol[j] = if sc == &b[j - 1] {
+                current
+            } else {
+                cmp::min(cmp::min(cmp::min(current, next), dcol[j - 1]) + 1, over)
+            };
+            row_min = cmp::min(row_min, dcol[j]);
+            current = next;
+        }
+        if row_min > limit {
+            return None;
+        }
+    }
+    Some(dcol[b.len()]).filter(|&dist| dist <= limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRS: &[(&str, &str)] = &[
+        ("", ""),
+        ("", "abc"),
+        ("kitten", "sitting"),
+        ("flaw", "lawn"),
+        ("abcabc", "abc"),
+        ("äbc", "abc"),
+        ("Märy häd", "Mary had"),
+        ("prefix_same_suffix", "prefix_diff_suffix"),
+    ];
+
+    #[test]
+    fn test_kernels_agree() {
+        for &(a, b) in PAIRS {
+            let expected = scalar(a, b);
+            assert_eq!(distance(a, b, None), expected, "{:?} {:?}", a, b);
+            assert_eq!(distance(b, a, None), expected, "{:?} {:?}", b, a);
+            if a.is_ascii() && b.is_ascii() {
+                assert_eq!(slice(a.as_bytes(), b.as_bytes()), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalar_multibyte() {
+        // Byte lengths far exceed char counts in all of these.
+        assert_eq!(scalar("日本語", "日本人"), 1);
+        assert_eq!(scalar("日本語日本語", "日本"), 4);
+        assert_eq!(scalar("🦀🦀🦀", "🦀"), 2);
+        assert_eq!(scalar("🦀", "🦀🦀🦀"), 2);
+        assert_eq!(scalar("", "𝔘𝔫𝔦𝔠𝔬𝔡𝔢"), 7);
+        assert_eq!(scalar("𝔘𝔫𝔦𝔠𝔬𝔡𝔢", ""), 7);
+        assert_eq!(scalar("a𝔘b", "𝔘"), 2);
+    }
+
+    #[test]
+    fn test_myers() {
+        for &(a, b) in PAIRS {
+            let expected = scalar(a, b);
+            let (a_chars, b_chars): (Vec<char>, Vec<char>) =
+                (a.chars().collect(), b.chars().collect());
+            assert_eq!(
+                myers_chars(&a_chars, b.chars()),
+                expected,
+                "{:?} {:?}",
+                a,
+                b
+            );
+            assert_eq!(
+                myers_chars(&b_chars, a.chars()),
+                expected,
+                "{:?} {:?}",
+                b,
+                a
+            );
+            if a.is_ascii() && b.is_ascii() {
+                assert_eq!(myers_bytes(a.as_bytes(), b.as_bytes()), expected);
+            }
+        }
+
+        // Patterns filling the whole word.
+        let a: String = (0..64usize)
+            .map(|i| (b'a' + (i % 26) as u8) as char)
+            .collect();
+        let b: String = (0..70usize)
+            .map(|i| (b'a' + (i * 7 % 26) as u8) as char)
+            .collect();
+        assert_eq!(
+            myers_bytes(a.as_bytes(), b.as_bytes()),
+            slice(a.as_bytes(), b.as_bytes())
+        );
+        assert_eq!(distance(&a, &b, None), slice(a.as_bytes(), b.as_bytes()));
+    }
+
+    #[test]
+    fn test_distance_with_limit() {
+        assert_eq!(distance("kitten", "sitting", Some(3)), 3);
+        assert!(distance("a", "abcdef", Some(2)) > 2);
+        assert!(distance("ä", "äöüäöü", Some(2)) > 2);
+    }
+
+    #[test]
+    fn test_banded() {
+        for &(a, b) in PAIRS {
+            let (a, b) = (a.as_bytes(), b.as_bytes());
+            let expected = slice(a, b);
+            let mut cells = 0;
+            assert_eq!(banded(a, b, expected, &mut cells), Some(expected));
+            if expected > 0 {
+                assert_eq!(banded(a, b, expected - 1, &mut cells), None);
+            }
+        }
+
+        // Only the band of width 3 is computed, and the second row already
+        // exceeds the limit everywhere.
+        let mut cells = 0;
+        assert_eq!(banded(b"abcdefgh", b"zyxwvuts", 1, &mut cells), None);
+        assert_eq!(cells, 5);
+
+        let a: Vec<u8> = (0..200).map(|i| b'a' + (i % 26) as u8).collect();
+        let mut b = a.clone();
+        b[100] = b'!';
+        b.remove(150);
+        let mut cells = 0;
+        assert_eq!(banded(&a, &b, 2, &mut cells), Some(2));
+        assert!(cells <= 5 * a.len());
+    }
+
+    #[test]
+    fn test_distance_counted() {
+        let mut stats = QueryStats::default();
+        distance_counted("kitten", "sitting", None, &mut stats);
+        assert_eq!(stats.cells_computed, 42);
+        distance_counted("prefix_a_suffix", "prefix_bc_suffix", None, &mut stats);
+        assert_eq!(stats.cells_computed, 44);
+        distance_counted("a", "abcdef", Some(2), &mut stats);
+        assert_eq!(stats.early_exits, 1);
+
+        // Too long for Myers, and sharing too few trigrams for the band.
+        let a: String = (0..100).map(|i| (b'a' + (i % 26) as u8) as char).collect();
+        let b: String = (0..100)
+            .map(|i| (b'a' + (i * 7 % 26) as u8) as char)
+            .collect();
+        let cells = stats.cells_computed;
+        assert!(distance_counted(&a, &b, Some(5), &mut stats) > 5);
+        assert_eq!((stats.early_exits, stats.cells_computed), (2, cells));
+        assert_eq!(
+            distance_counted(&a, &a.replace('k', "K"), Some(5), &mut stats),
+            4
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Physical key positions of common keyboard layouts.
+
+/// A keyboard layout, used to tell which keys are next to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Keyboard {
+    /// The US QWERTY layout.
+    Qwerty,
+    /// The French AZERTY layout.
+    Azerty,
+    /// The US Dvorak layout.
+    Dvorak,
+}
+
+/// How far each row is shifted to the right, in key widths.
+const ROW_OFFSETS: [f32; 4] = [0.0, 0.5, 0.75, 1.25];
+
+impl Keyboard {
+    /// The unshifted keys of each row, from the number row down.
+    fn rows(self) -> [&'static str; 4] {
+        match self {
+            Keyboard::Qwerty => ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"],
+            Keyboard::Azerty => ["&é\"'(-è_çà)=", "azertyuiop^$", "qsdfghjklmù", "wxcvbn,;:!"],
+            Keyboard::Dvorak => ["1234567890[]", "',.pyfgcrl/=", "aoeuidhtns-", ";qjkxbmwvz"],
+        }
+    }
+
+    /// The row and horizontal position of the key producing `c`.
+    fn position(self, c: char) -> Option<(usize, f32)> {
+        let c = c.to_lowercase().next()?;
+        self.rows().iter().enumerate().find_map(|(row, keys)| {
+            let col = keys.chars().position(|key| key == c)?;
+            Some((row, col as f32 + ROW_OFFSETS[row]))
+        })
+    }
+
+    /// Whether `a` and `b` are produced by distinct keys that touch each
+    /// other, ignoring case.
+    pub fn adjacent(self, a: char, b: char) -> bool {
+        match (self.position(a), self.position(b)) {
+            (Some((row_a, x_a)), Some((row_b, x_b))) => {
+                row_a.abs_diff(row_b) <= 1
+                    && (x_a - x_b).abs() <= 1.0
+                    && (row_a, x_a) != (row_b, x_b)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent() {
+        for (a, b) in [
+            ('e', 'w'),
+            ('e', 'd'),
+            ('e', 'S'),
+            ('d', 'x'),
+            ('d', 'c'),
+            ('1', 'q'),
+        ] {
+            assert!(Keyboard::Qwerty.adjacent(a, b), "{} {}", a, b);
+        }
+        for (a, b) in [('e', 'e'), ('e', 'f'), ('d', 'z'), ('q', 'p'), ('e', 'ü')] {
+            assert!(!Keyboard::Qwerty.adjacent(a, b), "{} {}", a, b);
+        }
+        assert!(Keyboard::Azerty.adjacent('z', 'e'));
+        assert!(!Keyboard::Qwerty.adjacent('z', 'e'));
+        assert!(Keyboard::Dvorak.adjacent('a', 'o'));
+    }
+}
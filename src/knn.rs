@@ -0,0 +1,140 @@
+//! k-nearest-neighbour queries.
+
+use crate::{kernel, BkTree, Distance, VpTree};
+
+/// A collection of strings that can find the ones closest to a lookup, such
+/// as a [`BkTree`], a [`VpTree`], or a plain slice scanned linearly.
+pub trait NearestNeighbors {
+    /// How far apart two strings are.
+    type Distance;
+
+    /// Finds the `k` strings closest to `lookup`, closest first.
+    fn nearest(&self, lookup: &str, k: usize) -> Vec<(&str, Self::Distance)>;
+}
+
+impl NearestNeighbors for BkTree {
+    type Distance = Distance;
+
+    fn nearest(&self, lookup: &str, k: usize) -> Vec<(&str, Distance)> {
+        BkTree::nearest(self, lookup, k)
+    }
+}
+
+impl<F> NearestNeighbors for VpTree<F>
+where
+    F: Fn(&str, &str) -> f64,
+{
+    type Distance = f64;
+
+    fn nearest(&self, lookup: &str, k: usize) -> Vec<(&str, f64)> {
+        VpTree::nearest(self, lookup, k)
+    }
+}
+
+/// A linear scan, in which farther items are rejected without computing
+/// their full distance once `k` items have been found. Items at the same
+/// distance keep their original order.
+impl<T: AsRef<str>> NearestNeighbors for [T] {
+    type Distance = Distance;
+
+    fn nearest(&self, lookup: &str, k: usize) -> Vec<(&str, Distance)> {
+        scan(lookup, self.iter().map(AsRef::as_ref), k)
+    }
+}
+
+impl<T: AsRef<str>> NearestNeighbors for Vec<T> {
+    type Distance = Distance;
+
+    fn nearest(&self, lookup: &str, k: usize) -> Vec<(&str, Distance)> {
+        self[..].nearest(lookup, k)
+    }
+}
+
+/// Finds the `k` items of `index` closest to `lookup`, with their
+/// distances, closest first.
+///
+/// Backed by whichever index is at hand: a slice is scanned linearly, while
+/// a [`BkTree`] or a [`VpTree`] built once answers repeated queries without
+/// looking at every item.
+pub fn knn<'a, I>(lookup: &str, index: &'a I, k: usize) -> Vec<(&'a str, I::Distance)>
+where
+    I: NearestNeighbors + ?Sized,
+{
+    index.nearest(lookup, k)
+}
+
+fn scan<'a>(
+    lookup: &str,
+    items: impl Iterator<Item = &'a str>,
+    k: usize,
+) -> Vec<(&'a str, Distance)> {
+    let mut nearest: Vec<(&str, Distance)> = Vec::with_capacity(k);
+    if k == 0 {
+        return nearest;
+    }
+    for item in items {
+        let limit = if nearest.len() == k {
+            match nearest[k - 1].1.get().checked_sub(1) {
+                Some(limit) => Some(limit),
+                // Nothing can beat `k` exact matches.
+                None => break,
+            }
+        } else {
+            None
+        };
+        let dist = kernel::distance(lookup, item, limit);
+        if limit.is_some_and(|limit| dist > limit) {
+            continue;
+        }
+        let dist = Distance(dist);
+        let pos = nearest.partition_point(|&(_, d)| d <= dist);
+        nearest.insert(pos, (item, dist));
+        nearest.truncate(k);
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_knn() {
+        let items = ["print", "println", "eprint", "format", "write"];
+        assert_eq!(
+            knn("prin", &items[..], 3),
+            vec![
+                ("print", Distance(1)),
+                ("eprint", Distance(2)),
+                ("println", Distance(3))
+            ]
+        );
+        assert_eq!(knn("prin", &items[..], 0), vec![]);
+        assert_eq!(knn("prin", &items[..], 10).len(), 5);
+        let owned: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+        assert_eq!(knn("prin", &owned, 3), knn("prin", &items[..], 3));
+    }
+
+    #[test]
+    fn test_knn_indexes() {
+        let items = ["print", "println", "eprint", "format", "write"];
+        let linear = knn("prin", &items[..], 3);
+        let bk_tree: BkTree = items.iter().copied().collect();
+        assert_eq!(knn("prin", &bk_tree, 3), linear);
+        let vp_tree = VpTree::new(items, |a: &str, b: &str| crate::lev_distance(a, b) as f64);
+        let linear: Vec<_> = linear
+            .into_iter()
+            .map(|(s, d)| (s, d.get() as f64))
+            .collect();
+        assert_eq!(knn("prin", &vp_tree, 3), linear);
+    }
+
+    #[test]
+    fn test_knn_ties() {
+        let items = ["ab", "ba", "aa", "bb"];
+        assert_eq!(
+            knn("a", &items[..], 2),
+            vec![("ab", Distance(1)), ("ba", Distance(1))]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Keyboard layout transliteration.
+//!
+//! Text typed with the wrong keyboard layout active ends up as an unrelated
+//! string of characters, e.g. `ghbdtn` instead of `привет`. The edit distance
+//! between the two is the full length of the word, so such typos can't be
+//! caught by Levenshtein alone. Remapping the lookup through the physical key
+//! positions first turns them into exact matches.
+
+use crate::find_best_match_for_name;
+
+/// Pairs of characters produced by the same physical key on the US QWERTY
+/// and the Russian ЙЦУКЕН layouts.
+const QWERTY_JCUKEN: &[(char, char)] = &[
+    ('`', 'ё'),
+    ('q', 'й'),
+    ('w', 'ц'),
+    ('e', 'у'),
+    ('r', 'к'),
+    ('t', 'е'),
+    ('y', 'н'),
+    ('u', 'г'),
+    ('i', 'ш'),
+    ('o', 'щ'),
+    ('p', 'з'),
+    ('[', 'х'),
+    (']', 'ъ'),
+    ('a', 'ф'),
+    ('s', 'ы'),
+    ('d', 'в'),
+    ('f', 'а'),
+    ('g', 'п'),
+    ('h', 'р'),
+    ('j', 'о'),
+    ('k', 'л'),
+    ('l', 'д'),
+    (';', 'ж'),
+    ('\'', 'э'),
+    ('z', 'я'),
+    ('x', 'ч'),
+    ('c', 'с'),
+    ('v', 'м'),
+    ('b', 'и'),
+    ('n', 'т'),
+    ('m', 'ь'),
+    (',', 'б'),
+    ('.', 'ю'),
+    ('~', 'Ё'),
+    ('{', 'Х'),
+    ('}', 'Ъ'),
+    (':', 'Ж'),
+    ('"', 'Э'),
+    ('<', 'Б'),
+    ('>', 'Ю'),
+];
+
+fn lookup_key(c: char) -> Option<char> {
+    QWERTY_JCUKEN.iter().find_map(|&(latin, cyrillic)| {
+        if c == latin {
+            Some(cyrillic)
+        } else if c == cyrillic {
+            Some(latin)
+        } else {
+            None
+        }
+    })
+}
+
+fn switch_char(c: char) -> char {
+    if let Some(switched) = lookup_key(c) {
+        return switched;
+    }
+    // Letters are only listed in lowercase; keep the case of the original.
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    match lookup_key(lower) {
+        Some(switched) if lower != c => switched.to_uppercase().next().unwrap_or(switched),
+        _ => c,
+    }
+}
+
+/// Retypes `s` as if the other keyboard layout had been active.
+///
+/// Characters are mapped between the US QWERTY and the Russian ЙЦУКЕН
+/// layouts in both directions; anything that has no counterpart is kept as is.
+pub fn switch_layout(s: &str) -> String {
+    s.chars().map(switch_char).collect()
+}
+
+/// Like [`find_best_match_for_name`], but also recognizes lookups typed with
+/// the wrong keyboard layout.
+///
+/// The lookup is matched as given first. Only if that yields nothing is it
+/// retyped with [`switch_layout`] and matched again.
+pub fn find_best_match_for_name_with_layout<T>(
+    iter_names: impl Iterator<Item = T> + Clone,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    find_best_match_for_name(iter_names.clone(), lookup, dist)
+        .or_else(|| find_best_match_for_name(iter_names, &switch_layout(lookup), dist))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_layout() {
+        assert_eq!(switch_layout("ghbdtn"), "привет");
+        assert_eq!(switch_layout("привет"), "ghbdtn");
+        assert_eq!(switch_layout("Ghbdtn"), "Привет");
+        assert_eq!(switch_layout("ytn? 123"), "нет? 123");
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_with_layout() {
+        let input = ["привет", "пока"];
+        assert_eq!(
+            find_best_match_for_name_with_layout(input.iter(), "ghbdtn", None),
+            Some("привет".to_string())
+        );
+
+        let input = ["hello", "world"];
+        assert_eq!(
+            find_best_match_for_name_with_layout(input.iter(), "руддщ", None),
+            Some("hello".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name_with_layout(input.iter(), "hellp", None),
+            Some("hello".to_string())
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! The longest common subsequence.
+
+use std::cmp;
+
+/// Finds the length in chars of the longest subsequence shared by two
+/// strings.
+pub fn lcs_length(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row = vec![0; b.len() + 1];
+    for sc in a.chars() {
+        let mut diagonal = 0;
+        for (j, &tc) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if sc == tc {
+                diagonal + 1
+            } else {
+                cmp::max(above, row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Finds the similarity of two strings in `0.0..=1.0` as twice their
+/// [`lcs_length`] over their total length, so `1.0` means equal.
+///
+/// Two empty strings have a ratio of `1.0`.
+pub fn lcs_ratio(a: &str, b: &str) -> f64 {
+    let total = a.chars().count() + b.chars().count();
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * lcs_length(a, b) as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcs() {
+        assert_eq!(lcs_length("ABCBDAB", "BDCABA"), 4);
+        assert_eq!(lcs_length("größe", "grosse"), 3);
+        assert_eq!(lcs_length("", "abc"), 0);
+        assert_eq!(lcs_ratio("abcd", "abxd"), 0.75);
+        assert_eq!(lcs_ratio("abc", "xyz"), 0.0);
+        assert_eq!(lcs_ratio("", ""), 1.0);
+    }
+}
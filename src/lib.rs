@@ -31,37 +31,231 @@
 //!
 //! [Levenshtein distance]: https://en.wikipedia.org/wiki/Levenshtein_distance
 
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
 use std::cmp;
 
+use static_match::eq_ignore_case;
+
+mod abbrev;
+mod adaptive;
+mod alias;
+#[cfg(feature = "allocator_api")]
+mod alloc_api;
+mod approx;
+#[cfg(feature = "arena")]
+mod arena;
+mod assert;
+mod automaton;
+mod bk_tree;
+mod buffer;
+mod case;
+mod cluster;
+mod confidence;
+mod config_key;
+mod const_distance;
+mod damerau;
+mod deletion_index;
+mod diacritics;
+mod difflib;
+mod distance;
+mod duplicates;
+mod edit_ops;
+#[cfg(feature = "english")]
+mod english;
+mod frequency;
+#[cfg(feature = "unicode-segmentation")]
+mod grapheme;
+mod hash;
+#[cfg(feature = "http-headers")]
+mod http_header;
+#[cfg(feature = "iso-codes")]
+mod iso_code;
+mod iter;
+mod jaro;
+#[cfg(feature = "json")]
+mod json_pointer;
+mod kernel;
+mod keyboard;
+mod knn;
+mod layout;
+mod lcs;
+mod map;
+mod matcher;
+mod matrix;
+mod metric;
+#[cfg(feature = "mime")]
+mod mime;
+mod minhash;
+mod ngram;
+mod no_match;
+#[cfg(feature = "unicode-normalization")]
+mod normalize;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod parse;
+mod phonetic;
+mod profile;
+mod report;
+mod schema;
+mod serde_error;
+mod session;
+#[cfg(feature = "simd")]
+mod simd;
+mod simhash;
+mod sorted;
+mod static_match;
+mod stats;
+mod sublime;
+mod substring;
+mod symbol;
+#[cfg(any(feature = "english", feature = "http-headers", feature = "mime"))]
+mod table;
+#[cfg(test)]
+mod test_util;
+mod threshold;
+mod token;
+mod trie;
+mod vp_tree;
+mod weighted;
+mod words;
+
+pub use abbrev::{
+    abbreviates, find_best_match_for_name_with_abbreviations, is_abbreviation, ABBREVIATIONS,
+};
+pub use adaptive::{adaptive_max_dist, find_best_match_for_name_adaptive};
+pub use alias::Aliases;
+#[cfg(feature = "allocator_api")]
+pub use alloc_api::{lev_distance_in, DistanceBufferIn};
+pub use approx::{find_approx, fuzzy_grep, Match, PatternSet};
+#[cfg(feature = "arena")]
+pub use arena::BatchContext;
+#[doc(hidden)]
+pub use assert::__check_suggestion;
+pub use automaton::{AutomatonState, LevenshteinAutomaton};
+pub use bk_tree::BkTree;
+pub use buffer::DistanceBuffer;
+pub use case::CaseFolding;
+pub use cluster::{cluster, Dendrogram, Linkage, Merge};
+pub use confidence::{suggestion_confidence, Calibration, SuggestionFeatures};
+pub use config_key::suggest_key;
+pub use const_distance::{const_lev_distance, CONST_MAX_LEN};
+pub use damerau::damerau_lev_distance;
+pub use deletion_index::DeletionIndex;
+pub use diacritics::strip_diacritics;
+pub use difflib::{gestalt_ratio, get_close_matches, DEFAULT_GESTALT_CUTOFF};
+pub use distance::Distance;
+pub use duplicates::{find_near_duplicates, group_near_duplicates, NearDuplicate};
+pub use edit_ops::{lev_edit_ops, EditOp};
+#[cfg(feature = "english")]
+pub use english::{english_words, suggest_word};
+pub use frequency::{find_best_match_by_frequency, WEIGHT_PER_EDIT};
+#[cfg(feature = "unicode-segmentation")]
+pub use grapheme::grapheme_lev_distance;
+#[cfg(feature = "http-headers")]
+pub use http_header::{suggest_header, HTTP_HEADERS};
+#[cfg(feature = "iso-codes")]
+pub use iso_code::{suggest_country_code, suggest_lang_code, COUNTRY_CODES, LANGUAGE_CODES};
+pub use iter::{IteratorExt, SortedByLevDistance, WithinLevDistance};
+pub use jaro::{
+    find_best_match_for_name_jaro_winkler, jaro, jaro_winkler, DEFAULT_MIN_JARO_WINKLER,
+};
+#[cfg(feature = "json")]
+pub use json_pointer::{suggest_json_pointer, PointerCorrection};
+pub use keyboard::Keyboard;
+pub use knn::{knn, NearestNeighbors};
+pub use layout::{find_best_match_for_name_with_layout, switch_layout};
+pub use lcs::{lcs_length, lcs_ratio};
+pub use map::{suggest_entry_for, suggest_key_for};
+use matcher::QueryContext;
+pub use matcher::{Fallback, Interrupt, Matcher, MatcherBuilder, QueryStats, TieBreak};
+pub use matrix::{lev_distance_matrix, DistanceMatrix};
+pub use metric::{
+    find_best_match_by_metric, CompositeMetric, JaroWinklerDistance, Metric, NormalizedLevenshtein,
+    PrefixDistance, StringMetric,
+};
+#[cfg(feature = "mime")]
+pub use mime::{suggest_mime, MIME_TYPES};
+pub use minhash::{fuzzy_join, LshIndex, MinHasher};
+pub use ngram::{dice_coefficient, ngram_cosine, ngram_similarity, trigram_similarity};
+pub use no_match::{try_find_best_match_for_name, NoMatch};
+#[cfg(feature = "unicode-normalization")]
+pub use normalize::Normalization;
+#[cfg(feature = "parallel")]
+pub use parallel::{par_find_best_match_for_name, par_find_best_matches_for_name};
+pub use parse::{FieldNames, FuzzyFromStr, FuzzyParseError};
+pub use phonetic::{double_metaphone, soundex};
+pub use profile::Profile;
+pub use report::{ScoreReport, Tier};
+pub use schema::{SchemaSuggester, UnknownIdentifier};
+pub use serde_error::enrich_serde_error;
+pub use session::QuerySession;
+#[cfg(feature = "simd")]
+pub use simd::lev_distance_many;
+pub use simhash::{hamming_distance, is_near_duplicate, simhash, simhash_with_ngram};
+pub use sorted::search_sorted;
+pub use static_match::{find_best_static_match, MAX_STATIC_LOOKUP_LEN};
+pub use stats::{distance_histogram, distance_stats, DistanceHistogram, DistanceStats};
+pub use sublime::sublime_score;
+pub use substring::{
+    find_best_match_for_name_with_substrings, lev_distance_with_substrings,
+    MIN_SUBSTRING_LOOKUP_LEN,
+};
+pub use symbol::{Interner, Symbol};
+pub use threshold::Threshold;
+pub use token::{lev_distance_words, token_jaccard};
+pub use trie::FuzzyTrie;
+pub use vp_tree::VpTree;
+pub use weighted::WeightedLevenshtein;
+pub use words::{find_match_by_sorted_words, to_snake_case, WordSplit};
+
+#[cfg(feature = "derive")]
+pub use lev_distance_derive::{FieldNames, FuzzyFromStr};
+
 /// Finds the Levenshtein distance between two strings.
 pub fn lev_distance(a: &str, b: &str) -> usize {
-    // cases which don't require further computation
-    if a.is_empty() {
-        return b.chars().count();
-    } else if b.is_empty() {
-        return a.chars().count();
-    }
+    kernel::distance(a, b, None)
+}
 
-    let mut dcol: Vec<_> = (0..=b.len()).collect();
-    let mut t_last = 0;
+/// Finds the Levenshtein distance between two slices, such as token streams
+/// or code points.
+pub fn lev_distance_slices<T: Eq>(a: &[T], b: &[T]) -> usize {
+    kernel::slice(a, b)
+}
 
-    for (i, sc) in a.chars().enumerate() {
-        let mut current = i;
-        dcol[0] = current + 1;
+/// Finds the Levenshtein distance between two strings if it is at most
+/// `limit`.
+///
+/// The computation stops as soon as the distance is known to exceed `limit`,
+/// which is much cheaper than [`lev_distance`] on dissimilar strings.
+pub fn lev_distance_at_most(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let dist = kernel::distance(a, b, Some(limit));
+    (dist <= limit).then_some(dist)
+}
 
-        for (j, tc) in b.chars().enumerate() {
-            let next = dcol[j + 1];
-            if sc == tc {
-                dcol[j + 1] = current;
-            } else {
-                dcol[j + 1] = cmp::min(current, next);
-                dcol[j + 1] = cmp::min(dcol[j + 1], dcol[j]) + 1;
-            }
-            current = next;
-            t_last = j;
-        }
+/// A lower bound on the Levenshtein distance between two strings: the
+/// difference of their lengths in chars, since every edit changes the length
+/// by at most one.
+///
+/// Much cheaper than the distance itself, so candidates whose bound already
+/// exceeds a threshold can be rejected up front. The matchers in this crate
+/// apply it to every candidate.
+pub fn lev_distance_lower_bound(a: &str, b: &str) -> usize {
+    if a.is_ascii() && b.is_ascii() {
+        a.len().abs_diff(b.len())
+    } else {
+        a.chars().count().abs_diff(b.chars().count())
     }
-    dcol[t_last + 1]
+}
+
+/// Finds the similarity of two strings in `0.0..=1.0`: one minus their
+/// Levenshtein distance relative to the longer string, so `1.0` means equal.
+///
+/// Unlike raw distances, similarities compare across words of different
+/// lengths. Two empty strings have a similarity of `1.0`.
+pub fn lev_similarity(a: &str, b: &str) -> f64 {
+    let len = cmp::max(a.chars().count(), b.chars().count());
+    1.0 - Distance::between(a, b).normalized(len)
 }
 
 /// Finds the best match for a given word in the given iterator.
@@ -72,75 +266,435 @@ pub fn lev_distance(a: &str, b: &str) -> usize {
 ///
 /// Besides Levenshtein, we use case insensitive comparison to improve accuracy
 /// on an edge case with a lower(upper)case letters mismatch.
+///
+/// The candidates are walked once, so the iterator need not be `Clone`. To
+/// turn individual tiers off, build a [`Matcher`] with
+/// [`MatcherBuilder::tier`].
 pub fn find_best_match_for_name<T>(
-    iter_names: impl Iterator<Item = T> + Clone,
+    iter_names: impl Iterator<Item = T>,
     lookup: &str,
     dist: Option<usize>,
 ) -> Option<String>
 where
     T: AsRef<str>,
 {
-    let max_dist = dist.unwrap_or_else(|| cmp::max(lookup.len(), 3) / 3);
+    find_best_match(iter_names, lookup, dist).map(|candidate| candidate.as_ref().to_string())
+}
 
-    // Priority of matches:
-    // 1. Exact case insensitive match
-    // 2. Levenshtein distance match
-    // 3. Sorted word match
+/// Like [`find_best_match_for_name`], also returning the distance between
+/// the match and the given word, e.g. to decide whether the suggestion is
+/// confident enough to show.
+pub fn find_best_match_for_name_with_distance<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<(String, Distance)>
+where
+    T: AsRef<str>,
+{
+    let pipeline = Pipeline {
+        threshold: dist.into(),
+        ..Pipeline::default()
+    };
+    pipeline
+        .find(iter_names, lookup, &mut QueryContext::default())
+        .map(|(candidate, dist)| (candidate.as_ref().to_string(), dist))
+}
 
-    // 1. Exact case insensitive match
-    for candidate in iter_names.clone() {
-        if candidate.as_ref().to_uppercase() == lookup.to_uppercase() {
-            return Some(candidate.as_ref().to_string());
+/// Finds the `k` best matches for a given word in the given iterator, best
+/// first.
+///
+/// Candidates are accepted by the same tiers as in
+/// [`find_best_match_for_name`] and ranked by tier, then by distance, then
+/// with ties broken as there, so the first match is always the one
+/// [`find_best_match_for_name`] finds.
+pub fn find_best_matches_for_name<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+    k: usize,
+) -> Vec<String>
+where
+    T: AsRef<str>,
+{
+    let pipeline = Pipeline {
+        threshold: dist.into(),
+        ..Pipeline::default()
+    };
+    let max_dist = pipeline.max_dist_for(lookup) as f64;
+    let mut stats = QueryStats::default();
+    let mut ranked: Vec<(Rank, T)> = iter_names
+        .enumerate()
+        .filter_map(|(i, candidate)| {
+            let rank = pipeline.rank(
+                &pipeline.metric,
+                candidate.as_ref(),
+                i,
+                lookup,
+                max_dist,
+                &mut stats,
+            )?;
+            Some((rank, candidate))
+        })
+        .collect();
+    ranked.sort_unstable_by_key(|&(rank, _)| rank);
+    ranked
+        .into_iter()
+        .take(k)
+        .map(|(_, candidate)| candidate.as_ref().to_string())
+        .collect()
+}
+
+/// The matching pipeline behind [`find_best_match_for_name`], returning the
+/// winning candidate itself.
+fn find_best_match<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<T>
+where
+    T: AsRef<str>,
+{
+    let pipeline = Pipeline {
+        threshold: dist.into(),
+        ..Pipeline::default()
+    };
+    pipeline
+        .find(iter_names, lookup, &mut QueryContext::default())
+        .map(|(candidate, _)| candidate)
+}
+
+/// Like [`find_best_match_for_name`], returning the matching item itself
+/// instead of a copy of its string, e.g. to keep an interned symbol or a
+/// `&'static str` without another lookup.
+pub fn find_best_match_item<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<T>
+where
+    T: AsRef<str>,
+{
+    find_best_match(iter_names, lookup, dist)
+}
+
+/// Like [`find_best_match_for_name`], returning the position of the match
+/// in `iter_names`.
+///
+/// Unlike [`find_best_match_index`], ties are broken exactly as in
+/// [`find_best_match_for_name`].
+pub fn find_best_match_position<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<usize>
+where
+    T: AsRef<str>,
+{
+    let indexed = iter_names.enumerate().map(|(i, c)| Indexed(i, c));
+    find_best_match(indexed, lookup, dist).map(|Indexed(i, _)| i)
+}
+
+/// Finds the index of the best match for a given word in `candidates`.
+///
+/// Behaves like [`find_best_match_for_name`], except that the earliest of
+/// equally good candidates always wins, so ordered registries get stable
+/// suggestions.
+pub fn find_best_match_index<T>(
+    candidates: &[T],
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<usize>
+where
+    T: AsRef<str>,
+{
+    let indexed = candidates.iter().enumerate().map(|(i, c)| Indexed(i, c));
+    let pipeline = Pipeline {
+        threshold: dist.into(),
+        tie_break: TieBreak::Earliest,
+        ..Pipeline::default()
+    };
+    pipeline
+        .find(indexed, lookup, &mut QueryContext::default())
+        .map(|(Indexed(i, _), _)| i)
+}
+
+/// A candidate matched along with its position.
+#[derive(Clone, Copy)]
+pub(crate) struct Indexed<T>(usize, T);
+
+impl<T: AsRef<str>> AsRef<str> for Indexed<T> {
+    fn as_ref(&self) -> &str {
+        self.1.as_ref()
+    }
+}
+
+/// The options of the matching pipeline, which default to the behavior of
+/// [`find_best_match_for_name`].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Pipeline {
+    pub(crate) threshold: Threshold,
+    pub(crate) metric: Metric,
+    pub(crate) tie_break: TieBreak,
+    pub(crate) case_sensitive: bool,
+    pub(crate) case_folding: CaseFolding,
+    pub(crate) word_split: WordSplit,
+    /// The most edits forgiven for a prefix shared with the lookup.
+    pub(crate) prefix_bonus: usize,
+    /// The n-gram size of the pre-filter of the Levenshtein tier, or zero
+    /// if it is off.
+    pub(crate) ngram_prefilter: usize,
+    /// A bit for every [`Tier`] that is turned off.
+    disabled_tiers: u8,
+}
+
+impl Pipeline {
+    /// Whether `tier` may accept candidates.
+    pub(crate) fn enabled(&self, tier: Tier) -> bool {
+        self.disabled_tiers & (1 << tier as u8) == 0
+    }
+
+    pub(crate) fn set_enabled(&mut self, tier: Tier, enabled: bool) {
+        if enabled {
+            self.disabled_tiers &= !(1 << tier as u8);
+        } else {
+            self.disabled_tiers |= 1 << tier as u8;
         }
     }
 
-    // 2. Levenshtein distance match
-    let levenshtein_match = iter_names
-        .clone()
-        .filter_map(|name| {
-            let dist = lev_distance(lookup, name.as_ref());
-            if dist <= max_dist {
-                Some((name, dist))
-            } else {
-                None
+    /// The maximum allowable edit distance for `lookup`.
+    pub(crate) fn max_dist_for(&self, lookup: &str) -> usize {
+        self.threshold.max_dist(lookup)
+    }
+
+    /// Scores `candidate` in the Levenshtein tier by `metric`, returning its
+    /// distance to `lookup` and its score, which is the distance less the
+    /// prefix bonus. Only a score of at most `max_dist` is exact.
+    pub(crate) fn levenshtein_score<M>(
+        &self,
+        metric: &M,
+        candidate: &str,
+        lookup: &str,
+        max_dist: f64,
+        stats: &mut QueryStats,
+    ) -> (f64, f64)
+    where
+        M: StringMetric + ?Sized,
+    {
+        let bonus = if self.prefix_bonus == 0 {
+            0
+        } else {
+            let prefix = candidate
+                .chars()
+                .zip(lookup.chars())
+                .take_while(|(a, b)| a == b);
+            prefix.take(self.prefix_bonus).count()
+        };
+        let limit = max_dist + bonus as f64;
+        if self.ngram_prefilter > 0
+            && self.metric == Metric::Levenshtein
+            && !ngram::may_be_within(lookup, candidate, self.ngram_prefilter, limit as usize)
+        {
+            stats.early_exits += 1;
+            return (limit + 1.0, max_dist + 1.0);
+        }
+        let dist = metric.distance_within(lookup, candidate, limit, stats);
+        (dist, (dist - bonus as f64).max(0.0))
+    }
+
+    /// Whether the first tier accepts `candidate`.
+    pub(crate) fn is_exact(&self, candidate: &str, lookup: &str) -> bool {
+        if self.case_sensitive {
+            candidate == lookup
+        } else {
+            self.case_folding.eq(candidate, lookup)
+        }
+    }
+}
+
+impl Pipeline {
+    /// Ranks `candidate`, the `index`th, against `lookup` by the tiers of
+    /// [`find_best_match_in_context`] with the Levenshtein tier scored by
+    /// `metric`, or `None` if no tier accepts it. The
+    /// lowest rank is the candidate that [`find_best_match_in_context`]
+    /// picks.
+    pub(crate) fn rank<M>(
+        &self,
+        metric: &M,
+        candidate: &str,
+        index: usize,
+        lookup: &str,
+        max_dist: f64,
+        stats: &mut QueryStats,
+    ) -> Option<Rank>
+    where
+        M: StringMetric + ?Sized,
+    {
+        let rank = |tier, score, order| Some(Rank { tier, score, order });
+        if self.enabled(Tier::CaseInsensitive) {
+            if candidate == lookup {
+                return rank(0, 0.0, index);
+            } else if self.is_exact(candidate, lookup) {
+                return rank(1, 0.0, index);
             }
-        })
-        // Here we are collecting the next structure:
-        // (levenshtein_match, levenshtein_distance)
-        .fold(None, |result, (candidate, dist)| match result {
-            None => Some((candidate, dist)),
-            Some((c, d)) => Some(if dist < d { (candidate, dist) } else { (c, d) }),
-        });
+        }
+        if self.enabled(Tier::Levenshtein) {
+            stats.candidates_scanned += 1;
+            let (_, score) = self.levenshtein_score(metric, candidate, lookup, max_dist, stats);
+            if score <= max_dist {
+                return rank(2, score, index);
+            }
+        }
+        if self.enabled(Tier::SortedWords) && self.word_split.same_words(candidate, lookup) {
+            // The latest candidate wins among those with the same words,
+            // unless the earliest always does.
+            let order = match self.tie_break {
+                TieBreak::Compatible => usize::MAX - index,
+                TieBreak::Earliest => index,
+            };
+            return rank(3, 0.0, order);
+        }
+        None
+    }
 
-    // 3. Sorted word match
-    if levenshtein_match.is_some() {
-        levenshtein_match.map(|(candidate, _)| candidate.as_ref().to_string())
-    } else {
-        find_match_by_sorted_words(iter_names, lookup)
+    /// Runs [`find_best_match_in_context`] with the metric and threshold of
+    /// the pipeline.
+    pub(crate) fn find<T>(
+        &self,
+        iter_names: impl Iterator<Item = T>,
+        lookup: &str,
+        ctx: &mut QueryContext,
+    ) -> Option<(T, Distance)>
+    where
+        T: AsRef<str>,
+    {
+        let max_dist = self.max_dist_for(lookup) as f64;
+        find_best_match_in_context(iter_names, lookup, self, &self.metric, max_dist, ctx)
+            .map(|(candidate, dist)| (candidate, Distance(dist as usize)))
     }
 }
 
-fn find_match_by_sorted_words<T>(
+/// How well a candidate matches under [`Pipeline::rank`], ordered best
+/// first.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Rank {
+    tier: u8,
+    score: f64,
+    /// The position of the candidate, or its reverse if later ones win.
+    order: usize,
+}
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.tier
+            .cmp(&other.tier)
+            .then(self.score.total_cmp(&other.score))
+            .then(self.order.cmp(&other.order))
+    }
+}
+
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Rank {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Rank {}
+
+/// Like [`find_best_match`], ranking the Levenshtein tier by `metric` with
+/// candidates at most `max_dist` away, recording the work done in `ctx` and
+/// giving up with the best match so far once `ctx` says to stop.
+///
+/// The candidates are walked once, tracking the best candidate of every tier
+/// at the same time.
+fn find_best_match_in_context<T, M>(
     iter_names: impl Iterator<Item = T>,
     lookup: &str,
-) -> Option<String>
+    pipeline: &Pipeline,
+    metric: &M,
+    max_dist: f64,
+    ctx: &mut QueryContext,
+) -> Option<(T, f64)>
 where
     T: AsRef<str>,
+    M: StringMetric + ?Sized,
 {
-    iter_names.fold(None, |result, candidate| {
-        if sort_by_words(candidate.as_ref()) == sort_by_words(lookup) {
-            Some(candidate.as_ref().to_string())
-        } else {
-            result
+    let tie_break = pipeline.tie_break;
+    let full_distance = |c: &T| metric.distance(lookup, c.as_ref());
+
+    // Priority of matches:
+    // 1. Exact case insensitive match
+    // 2. Levenshtein distance match
+    // 3. Sorted word match
+    let mut exact_match = None;
+    let mut levenshtein_match: Option<(T, f64, f64)> = None;
+    let mut sorted_words_match = None;
+    for candidate in iter_names {
+        if ctx.should_stop() {
+            break;
+        }
+        let name = candidate.as_ref();
+
+        // 1. Exact case insensitive match, where nothing beats an exact case
+        // sensitive one
+        if pipeline.enabled(Tier::CaseInsensitive) {
+            if name == lookup {
+                return Some((candidate, 0.0));
+            } else if exact_match.is_some() {
+                continue;
+            } else if pipeline.is_exact(name, lookup) {
+                exact_match = Some(candidate);
+                continue;
+            }
         }
-    })
-}
 
-fn sort_by_words(name: &str) -> String {
-    let mut split_words: Vec<&str> = name.split('_').collect();
-    // We are sorting primitive &strs and can use unstable sort here.
-    split_words.sort_unstable();
-    split_words.join("_")
+        // 2. Levenshtein distance match
+        if pipeline.enabled(Tier::Levenshtein) {
+            ctx.stats.candidates_scanned += 1;
+            let (dist, score) =
+                pipeline.levenshtein_score(metric, name, lookup, max_dist, &mut ctx.stats);
+            if score <= max_dist {
+                if levenshtein_match
+                    .as_ref()
+                    .is_none_or(|&(_, _, s)| score < s)
+                {
+                    levenshtein_match = Some((candidate, dist, score));
+                }
+                if dist == 0.0 {
+                    // Nothing later can beat it.
+                    break;
+                }
+                continue;
+            }
+        }
+
+        // 3. Sorted word match
+        if levenshtein_match.is_none()
+            && !(tie_break == TieBreak::Earliest && sorted_words_match.is_some())
+            && pipeline.enabled(Tier::SortedWords)
+            && pipeline.word_split.same_words(name, lookup)
+        {
+            sorted_words_match = Some(candidate);
+        }
+    }
+
+    if let Some(candidate) = exact_match {
+        let dist = full_distance(&candidate);
+        Some((candidate, dist))
+    } else if levenshtein_match.is_some() || ctx.stopped() {
+        levenshtein_match.map(|(candidate, dist, _)| (candidate, dist))
+    } else {
+        sorted_words_match.map(|candidate| {
+            let dist = full_distance(&candidate);
+            (candidate, dist)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -167,6 +721,53 @@ mod tests {
     }
 
     #[test]
+    fn test_lev_distance_slices() {
+        assert_eq!(
+            lev_distance_slices(&["let", "x", "=", "1"], &["let", "y", "=", "1", ";"]),
+            2
+        );
+        assert_eq!(lev_distance_slices::<u32>(&[], &[1, 2, 3]), 3);
+        let (a, b): (Vec<u32>, Vec<u32>) = (
+            "kitten".chars().map(u32::from).collect(),
+            "sitting".chars().map(u32::from).collect(),
+        );
+        assert_eq!(lev_distance_slices(&a, &b), 3);
+    }
+
+    #[test]
+    fn test_lev_distance_at_most() {
+        assert_eq!(lev_distance_at_most("kitten", "sitting", 3), Some(3));
+        assert_eq!(lev_distance_at_most("kitten", "sitting", 2), None);
+        assert_eq!(lev_distance_at_most("äbc", "abc", 1), Some(1));
+        assert_eq!(lev_distance_at_most("", "", 0), Some(0));
+    }
+
+    #[test]
+    fn test_lev_distance_lower_bound() {
+        for (a, b) in [
+            ("kitten", "sitting"),
+            ("", "abc"),
+            ("größe", "grosse"),
+            ("ab", "ba"),
+        ] {
+            let bound = lev_distance_lower_bound(a, b);
+            assert_eq!(bound, lev_distance_lower_bound(b, a));
+            assert!(bound <= lev_distance(a, b));
+        }
+        assert_eq!(lev_distance_lower_bound("größe", "g"), 4);
+        assert_eq!(lev_distance_lower_bound("abc", ""), 3);
+    }
+
+    #[test]
+    fn test_lev_similarity() {
+        assert_eq!(lev_similarity("kitten", "kitten"), 1.0);
+        assert_eq!(lev_similarity("abcd", "abce"), 0.75);
+        assert_eq!(lev_similarity("äöü", "xyz"), 0.0);
+        assert_eq!(lev_similarity("", ""), 1.0);
+    }
+
+    #[test]
+    #[allow(clippy::useless_vec)]
     fn test_find_best_match_for_name() {
         let input = vec!["aaab", "aaabc"];
         assert_eq!(
@@ -199,6 +800,134 @@ mod tests {
     }
 
     #[test]
+    fn test_find_best_match_for_name_exact_beats_case() {
+        // An exact match beats an earlier one differing in case.
+        let input = ["Print", "print", "PRINT"];
+        assert_eq!(
+            find_best_match_for_name(input.iter(), "print", None),
+            Some("print".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_case_folding() {
+        let input = ["STRASSE", "ΟΔΟΣ"];
+        assert_eq!(
+            find_best_match_for_name(input.iter(), "straße", Some(0)),
+            Some("STRASSE".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name(input.iter(), "οδος", Some(0)),
+            Some("ΟΔΟΣ".to_string())
+        );
+        assert_eq!(find_best_match_for_name(["I"].iter(), "ı", Some(0)), None);
+    }
+
+    #[test]
+    fn test_find_best_match_index() {
+        let input = ["aaab", "aaac", "aaab"];
+        assert_eq!(find_best_match_index(&input, "aaaa", None), Some(0));
+        assert_eq!(find_best_match_index(&input, "1111111111", None), None);
+
+        let input = ["b_a_c", "c_b_a"];
+        assert_eq!(
+            find_best_match_for_name(input.iter(), "a_b_c", None),
+            Some("c_b_a".to_string())
+        );
+        assert_eq!(find_best_match_index(&input, "a_b_c", None), Some(0));
+    }
+
+    #[test]
+    fn test_find_best_match_item() {
+        const NAMES: [&str; 4] = ["println", "b_a_c", "c_b_a", "print"];
+        let found: Option<&&'static str> = find_best_match_item(NAMES.iter(), "printl", None);
+        assert_eq!(found, Some(&"println"));
+        assert_eq!(find_best_match_item(NAMES.iter(), "zzz", None), None);
+
+        assert_eq!(
+            find_best_match_position(NAMES.iter(), "prin", None),
+            Some(3)
+        );
+        // The last of the candidates with the same words wins.
+        assert_eq!(
+            find_best_match_position(NAMES.iter(), "a_b_c", None),
+            Some(2)
+        );
+        assert_eq!(find_best_match_index(&NAMES, "a_b_c", None), Some(1));
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_with_distance() {
+        let input = ["aaab", "AAAA", "b_a"];
+        assert_eq!(
+            find_best_match_for_name_with_distance(input.iter(), "aaac", None),
+            Some(("aaab".to_string(), Distance(1)))
+        );
+        assert_eq!(
+            find_best_match_for_name_with_distance(input.iter(), "aaaa", None),
+            Some(("AAAA".to_string(), Distance(4)))
+        );
+        assert_eq!(
+            find_best_match_for_name_with_distance(input.iter(), "a_b", None),
+            Some(("b_a".to_string(), Distance(2)))
+        );
+        assert_eq!(
+            find_best_match_for_name_with_distance(input.iter(), "zzzz", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_best_matches_for_name() {
+        let input = [
+            "prin", "print", "PRINT", "println", "eprint", "format", "t_prin",
+        ];
+        assert_eq!(
+            find_best_matches_for_name(input.iter(), "print", None, 3),
+            ["print", "PRINT", "prin"]
+        );
+        assert_eq!(
+            find_best_matches_for_name(input.iter(), "print", Some(2), 10),
+            ["print", "PRINT", "prin", "eprint", "println"]
+        );
+        assert_eq!(
+            find_best_matches_for_name(input.iter(), "prin_t", Some(0), 10),
+            ["t_prin"]
+        );
+        assert!(find_best_matches_for_name(input.iter(), "print", None, 0).is_empty());
+
+        let input = ["Print", "print", "PRINT"];
+        assert_eq!(
+            find_best_matches_for_name(input.iter(), "print", None, 2),
+            ["print", "Print"]
+        );
+        assert_eq!(find_best_static_match(&input, "print", None), Some("print"));
+    }
+
+    #[test]
+    fn test_find_best_matches_for_name_agrees_with_find_best_match_for_name() {
+        let cases: &[(&[&str], &str, Option<usize>)] = &[
+            (&["c_b_a", "b_c_a"], "a_b_c", None),
+            (&["Print", "print", "PRINT"], "print", None),
+            (&["PRINT", "Print"], "print", Some(0)),
+            (&["STRASSE", "strasse"], "straße", Some(0)),
+            (&["aaab", "aaac", "aaab"], "aaaa", None),
+            (&["prin", "print", "eprint", "t_prin"], "prin_t", Some(0)),
+            (&["abc"], "xyz", None),
+        ];
+        for &(input, lookup, dist) in cases {
+            let best = find_best_match_for_name(input.iter(), lookup, dist);
+            let top = find_best_matches_for_name(input.iter(), lookup, dist, 1);
+            assert_eq!(top, Vec::from_iter(best), "{:?} {:?}", input, lookup);
+        }
+        assert_eq!(
+            find_best_matches_for_name(["c_b_a", "b_c_a"].iter(), "a_b_c", None, 2),
+            ["b_c_a", "c_b_a"]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::useless_vec)]
     fn test_find_best_match_for_name_from_strings() {
         let input = vec!["aaab".to_string(), "aaabc".to_string()];
         assert_eq!(
@@ -230,6 +959,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_best_match_for_name_from_lines() {
+        use std::io::{BufRead, Cursor};
+
+        // `Lines` can only be walked once.
+        let lines = || {
+            Cursor::new("println\nb_a_c\nPrint\n")
+                .lines()
+                .map(Result::unwrap)
+        };
+        assert_eq!(
+            find_best_match_for_name(lines(), "print", None),
+            Some("Print".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name(lines(), "printn", None),
+            Some("println".to_string())
+        );
+        assert_eq!(
+            find_best_match_for_name(lines(), "a_b_c", None),
+            Some("b_a_c".to_string())
+        );
+    }
+
     #[test]
     fn test_find_best_match_for_name_from_hashset() {
         use std::collections::HashSet;
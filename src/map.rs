@@ -0,0 +1,62 @@
+//! Suggestions among the keys of a map.
+
+use crate::find_best_match;
+
+/// A map entry matched by its key.
+#[derive(Clone, Copy)]
+struct Entry<'a, K, V>(&'a K, &'a V);
+
+impl<K: AsRef<str>, V> AsRef<str> for Entry<'_, K, V> {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+/// Finds the key of `map` closest to `lookup`, like
+/// [`find_best_match_for_name`](crate::find_best_match_for_name) with the
+/// default threshold.
+///
+/// Works with any map whose reference iterates over `(&K, &V)`, such as
+/// [`HashMap`](std::collections::HashMap) and
+/// [`BTreeMap`](std::collections::BTreeMap).
+pub fn suggest_key_for<'a, M, K, V>(map: &'a M, lookup: &str) -> Option<&'a K>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: AsRef<str> + 'a,
+    V: 'a,
+{
+    suggest_entry_for(map, lookup).map(|(key, _)| key)
+}
+
+/// Like [`suggest_key_for`], also returning the value of the key.
+pub fn suggest_entry_for<'a, M, K, V>(map: &'a M, lookup: &str) -> Option<(&'a K, &'a V)>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: AsRef<str> + 'a,
+    V: 'a,
+{
+    let entries = map.into_iter().map(|(key, value)| Entry(key, value));
+    find_best_match(entries, lookup, None).map(|Entry(key, value)| (key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+
+    #[test]
+    fn test_suggest_key_for() {
+        let map: HashMap<String, u16> = [("http".to_string(), 80), ("https".to_string(), 443)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            suggest_key_for(&map, "htps").map(String::as_str),
+            Some("https")
+        );
+        assert_eq!(suggest_key_for(&map, "ftp"), None);
+
+        let map: BTreeMap<&str, u16> = [("ssh", 22), ("smtp", 25)].into_iter().collect();
+        assert_eq!(suggest_entry_for(&map, "smt"), Some((&"smtp", &25)));
+    }
+}
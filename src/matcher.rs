@@ -0,0 +1,1202 @@
+//! A reusable, configurable matcher.
+
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::jaro::jaro_winkler;
+use crate::metric::CustomMetric;
+#[cfg(feature = "unicode-normalization")]
+use crate::normalize::Normalization;
+use crate::profile::{self, Profile};
+use crate::token::{token_jaccard, token_set_ratio};
+use crate::{
+    double_metaphone, find_best_match_in_context, soundex, strip_diacritics, to_snake_case,
+    CaseFolding, Indexed, Metric, Pipeline, Rank, StringMetric, Threshold, Tier, WordSplit,
+};
+
+/// Counters describing the work done by a query.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryStats {
+    /// The number of candidates whose distance was considered.
+    pub candidates_scanned: usize,
+    /// The number of DP cells computed across all distance computations.
+    pub cells_computed: usize,
+    /// The number of distance computations skipped because the inputs could
+    /// not be within the threshold.
+    pub early_exits: usize,
+    /// Whether the query ran out of time and returned the best match found
+    /// until then.
+    pub timed_out: bool,
+    /// Whether the query was cancelled and returned the best match found
+    /// until then.
+    pub cancelled: bool,
+}
+
+/// Conditions under which a long-running query gives up early, returning
+/// the best result found so far.
+#[derive(Clone, Debug, Default)]
+pub struct Interrupt {
+    deadline: Option<Instant>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl Interrupt {
+    /// Creates an interrupt that never fires.
+    pub fn new() -> Self {
+        Interrupt::default()
+    }
+
+    /// Gives up once `deadline` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Gives up once `flag` is set, e.g. by an editor when the user keeps
+    /// typing.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+}
+
+/// Which of several equally good candidates wins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Like [`find_best_match_for_name`](crate::find_best_match_for_name):
+    /// the earliest candidate wins at equal edit distance, but the latest
+    /// wins among candidates with the same words.
+    #[default]
+    Compatible,
+    /// The earliest candidate always wins.
+    Earliest,
+}
+
+/// A secondary metric for candidates that no tier accepted.
+///
+/// The candidate scoring highest under the metric wins if it reaches the
+/// minimum, which is a similarity in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fallback {
+    /// The Jaro–Winkler similarity, which favors shared prefixes.
+    JaroWinkler(f64),
+    /// The token set ratio, which ignores word order and extra words.
+    TokenSetRatio(f64),
+    /// The [`token_jaccard`] similarity over the words split by the given
+    /// [`WordSplit`], for multi-word strings such as error messages.
+    TokenJaccard(WordSplit, f64),
+    /// Candidates with the same [`soundex`] code as the lookup, such as
+    /// "Smyth" for "Smith", scored by their Jaro–Winkler similarity.
+    Soundex,
+    /// Candidates sharing a [`double_metaphone`] code with the lookup,
+    /// primary or alternate, scored by their Jaro–Winkler similarity.
+    DoubleMetaphone,
+}
+
+impl Fallback {
+    /// Scores `candidate` against `lookup`, or `None` if it falls short of
+    /// the minimum.
+    fn score(self, candidate: &str, lookup: &str) -> Option<f64> {
+        let (score, min) = match self {
+            Fallback::JaroWinkler(min) => (jaro_winkler(candidate, lookup), min),
+            Fallback::TokenSetRatio(min) => (token_set_ratio(candidate, lookup), min),
+            Fallback::TokenJaccard(split, min) => (token_jaccard(candidate, lookup, split), min),
+            Fallback::Soundex => {
+                let code = soundex(lookup);
+                if code.is_empty() || soundex(candidate) != code {
+                    return None;
+                }
+                (jaro_winkler(candidate, lookup), 0.0)
+            }
+            Fallback::DoubleMetaphone => {
+                let (primary, alternate) = double_metaphone(lookup);
+                let (p, a) = double_metaphone(candidate);
+                let shared = [&p, &a]
+                    .iter()
+                    .any(|code| !code.is_empty() && (**code == primary || **code == alternate));
+                if !shared {
+                    return None;
+                }
+                (jaro_winkler(candidate, lookup), 0.0)
+            }
+        };
+        (score >= min).then_some(score)
+    }
+}
+
+/// The state threaded through a single query.
+#[derive(Debug, Default)]
+pub(crate) struct QueryContext {
+    pub(crate) stats: QueryStats,
+    interrupt: Interrupt,
+    ticks: u32,
+}
+
+impl QueryContext {
+    /// How many checks to skip between reads of the clock.
+    const CLOCK_INTERVAL: u32 = 16;
+
+    pub(crate) fn new(interrupt: Interrupt) -> Self {
+        QueryContext {
+            interrupt,
+            ..QueryContext::default()
+        }
+    }
+
+    /// Whether the query should give up now.
+    pub(crate) fn should_stop(&mut self) -> bool {
+        if self.stopped() {
+            return true;
+        }
+        self.ticks = self.ticks.wrapping_add(1);
+        if let Some(deadline) = self.interrupt.deadline {
+            if self.ticks % Self::CLOCK_INTERVAL == 1 && Instant::now() >= deadline {
+                self.stats.timed_out = true;
+            }
+        }
+        if let Some(flag) = &self.interrupt.cancel_flag {
+            if flag.load(Ordering::Relaxed) {
+                self.stats.cancelled = true;
+            }
+        }
+        self.stopped()
+    }
+
+    /// Whether the query has given up.
+    pub(crate) fn stopped(&self) -> bool {
+        self.stats.timed_out || self.stats.cancelled
+    }
+}
+
+/// Finds best matches with a configuration chosen once up front.
+///
+/// Create one with [`Matcher::builder`], or use [`Matcher::default`] to get
+/// the behavior of [`find_best_match_for_name`](crate::find_best_match_for_name).
+#[derive(Debug, Default)]
+pub struct Matcher {
+    pipeline: Pipeline,
+    profile: Profile,
+    string_metric: Option<CustomMetric>,
+    fallback: Option<Fallback>,
+    #[cfg(feature = "unicode-normalization")]
+    normalization: Option<Normalization>,
+    ignore_diacritics: bool,
+    ignore_case: bool,
+    snake_case: bool,
+    collect_stats: bool,
+    time_budget: Option<Duration>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    last_stats: Mutex<QueryStats>,
+}
+
+impl Clone for Matcher {
+    fn clone(&self) -> Self {
+        Matcher {
+            pipeline: self.pipeline,
+            profile: self.profile,
+            string_metric: self.string_metric.clone(),
+            fallback: self.fallback,
+            #[cfg(feature = "unicode-normalization")]
+            normalization: self.normalization,
+            ignore_diacritics: self.ignore_diacritics,
+            ignore_case: self.ignore_case,
+            snake_case: self.snake_case,
+            collect_stats: self.collect_stats,
+            time_budget: self.time_budget,
+            cancel_flag: self.cancel_flag.clone(),
+            last_stats: Mutex::new(self.stats()),
+        }
+    }
+}
+
+impl Matcher {
+    /// Returns a builder for configuring a matcher.
+    pub fn builder() -> MatcherBuilder {
+        MatcherBuilder::default()
+    }
+
+    /// Finds the best match for `lookup` among `candidates`.
+    ///
+    /// If a time budget is configured, the query starts the clock now. If a
+    /// cancel flag is configured, the query gives up as soon as it is set.
+    pub fn best_match<T>(
+        &self,
+        candidates: impl Iterator<Item = T> + Clone,
+        lookup: &str,
+    ) -> Option<String>
+    where
+        T: AsRef<str>,
+    {
+        self.best_match_interruptible(candidates, lookup, &self.interrupt())
+    }
+
+    /// Finds the best match for `lookup` among `candidates`, returning the
+    /// best match found so far once `interrupt` fires.
+    ///
+    /// The configured time budget and cancel flag are ignored in favor of
+    /// `interrupt`.
+    pub fn best_match_interruptible<T>(
+        &self,
+        candidates: impl Iterator<Item = T> + Clone,
+        lookup: &str,
+        interrupt: &Interrupt,
+    ) -> Option<String>
+    where
+        T: AsRef<str>,
+    {
+        self.find(candidates, lookup, interrupt)
+            .map(|candidate| candidate.as_ref().to_string())
+    }
+
+    /// The pipeline behind [`Matcher::best_match_interruptible`], returning
+    /// the winning candidate itself.
+    fn find<T>(
+        &self,
+        candidates: impl Iterator<Item = T> + Clone,
+        lookup: &str,
+        interrupt: &Interrupt,
+    ) -> Option<T>
+    where
+        T: AsRef<str>,
+    {
+        if let Some(lookup) = self.preprocess(lookup) {
+            let candidates = candidates.map(|c| {
+                let preprocessed = self.preprocess(c.as_ref()).unwrap_or_default();
+                Preprocessed(preprocessed, c)
+            });
+            return self
+                .find_preprocessed(candidates, &lookup, interrupt)
+                .map(|Preprocessed(_, c)| c);
+        }
+        self.find_preprocessed(candidates, lookup, interrupt)
+    }
+
+    /// Like [`Matcher::find`], with any preprocessing already applied.
+    fn find_preprocessed<T>(
+        &self,
+        candidates: impl Iterator<Item = T> + Clone,
+        lookup: &str,
+        interrupt: &Interrupt,
+    ) -> Option<T>
+    where
+        T: AsRef<str>,
+    {
+        let mut ctx = QueryContext::new(interrupt.clone());
+        let best = match self.profile {
+            Profile::Default => None,
+            profile => {
+                profile::find_best_scored_match(candidates.clone(), lookup, profile, &mut ctx)
+            }
+        };
+        let best = match best {
+            Some(best) => Some(best),
+            None if ctx.stopped() => None,
+            None => {
+                let (pipeline, metric, max_dist) = self.levenshtein_stage(|| {
+                    self.pipeline
+                        .threshold
+                        .max_dist_among(candidates.clone(), lookup)
+                });
+                find_best_match_in_context(
+                    candidates.clone(),
+                    lookup,
+                    &pipeline,
+                    metric,
+                    max_dist,
+                    &mut ctx,
+                )
+                .map(|(candidate, _)| candidate)
+            }
+        };
+        let best = match (best, self.fallback) {
+            (None, Some(fallback)) if !ctx.stopped() => {
+                let mut best: Option<(f64, T)> = None;
+                for candidate in candidates {
+                    if ctx.should_stop() {
+                        break;
+                    }
+                    if let Some(score) = fallback.score(candidate.as_ref(), lookup) {
+                        if best.as_ref().is_none_or(|&(s, _)| score > s) {
+                            best = Some((score, candidate));
+                        }
+                    }
+                }
+                best.map(|(_, candidate)| candidate)
+            }
+            (best, _) => best,
+        };
+        self.record(&ctx);
+        best
+    }
+
+    /// Finds the index of the best match for `lookup` among `candidates`.
+    pub fn best_match_index<T>(
+        &self,
+        candidates: impl Iterator<Item = T> + Clone,
+        lookup: &str,
+    ) -> Option<usize>
+    where
+        T: AsRef<str>,
+    {
+        let indexed = candidates.enumerate().map(|(i, c)| Indexed(i, c));
+        self.find(indexed, lookup, &self.interrupt())
+            .map(|Indexed(i, _)| i)
+    }
+
+    /// Finds the best match for `lookup` among items compared by their
+    /// [`Display`](fmt::Display) output, returning the item itself.
+    ///
+    /// The items are walked once, each formatted into the same buffer and
+    /// scored there, so they need not be `Clone`. Without the candidates at
+    /// hand, [`Threshold::Adaptive`] is the same as [`Threshold::OneThird`].
+    pub fn best_match_display<T>(
+        &self,
+        candidates: impl IntoIterator<Item = T>,
+        lookup: &str,
+    ) -> Option<T>
+    where
+        T: fmt::Display,
+    {
+        let preprocessed = self.preprocess(lookup);
+        let lookup = preprocessed.as_deref().unwrap_or(lookup);
+        let (pipeline, metric, max_dist) =
+            self.levenshtein_stage(|| self.pipeline.max_dist_for(lookup));
+        let mut ctx = QueryContext::new(self.interrupt());
+        let mut buf = String::new();
+        let mut best: Option<(DisplayScore, T)> = None;
+        for (i, candidate) in candidates.into_iter().enumerate() {
+            if ctx.should_stop() {
+                break;
+            }
+            buf.clear();
+            // Writing to a `String` never fails.
+            let _ = write!(buf, "{}", candidate);
+            let preprocessed = self.preprocess(&buf);
+            let name = preprocessed.as_deref().unwrap_or(&buf);
+            let score = self.display_score(name, i, lookup, &pipeline, metric, max_dist, &mut ctx);
+            if let Some(score) = score {
+                if best.as_ref().is_none_or(|(s, _)| score.beats(s)) {
+                    best = Some((score, candidate));
+                }
+            }
+        }
+        self.record(&ctx);
+        best.map(|(_, candidate)| candidate)
+    }
+
+    /// Scores `name`, the `index`th candidate, by the first stage of
+    /// [`Matcher::find_preprocessed`] that accepts it.
+    #[allow(clippy::too_many_arguments)]
+    fn display_score(
+        &self,
+        name: &str,
+        index: usize,
+        lookup: &str,
+        pipeline: &Pipeline,
+        metric: &dyn StringMetric,
+        max_dist: f64,
+        ctx: &mut QueryContext,
+    ) -> Option<DisplayScore> {
+        if self.profile != Profile::Default {
+            ctx.stats.candidates_scanned += 1;
+            if let Some(score) = self.profile.score(name, lookup) {
+                return Some(DisplayScore::Profile(score, name.chars().count()));
+            }
+        }
+        if let Some(rank) = pipeline.rank(metric, name, index, lookup, max_dist, &mut ctx.stats) {
+            return Some(DisplayScore::Tier(rank));
+        }
+        let score = self.fallback?.score(name, lookup)?;
+        Some(DisplayScore::Fallback(score))
+    }
+
+    /// The pipeline, metric, and maximum distance of the Levenshtein tier,
+    /// with the maximum distance of the built-in metric from `max_dist`.
+    fn levenshtein_stage(
+        &self,
+        max_dist: impl FnOnce() -> usize,
+    ) -> (Pipeline, &dyn StringMetric, f64) {
+        match &self.string_metric {
+            // The prefix bonus and the n-gram pre-filter count whole edits,
+            // which a custom metric need not.
+            Some(custom) => (
+                Pipeline {
+                    prefix_bonus: 0,
+                    ngram_prefilter: 0,
+                    ..self.pipeline
+                },
+                custom.metric.as_ref(),
+                custom.max_distance,
+            ),
+            None => (self.pipeline, &self.pipeline.metric, max_dist() as f64),
+        }
+    }
+
+    /// The counters of the most recent query.
+    ///
+    /// Always zero unless enabled with [`MatcherBuilder::collect_stats`].
+    pub fn stats(&self) -> QueryStats {
+        *self.last_stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The form `s` is compared in, or `None` if it is compared as is.
+    fn preprocess(&self, s: &str) -> Option<String> {
+        let mut preprocessed = None;
+        #[cfg(feature = "unicode-normalization")]
+        if let Some(form) = self.normalization {
+            preprocessed = Some(form.normalize(s));
+        }
+        if self.ignore_diacritics {
+            preprocessed = Some(strip_diacritics(preprocessed.as_deref().unwrap_or(s)));
+        }
+        if self.snake_case {
+            preprocessed = Some(to_snake_case(preprocessed.as_deref().unwrap_or(s)));
+        }
+        if self.ignore_case {
+            let folding = self.pipeline.case_folding;
+            preprocessed = Some(folding.folded(preprocessed.as_deref().unwrap_or(s)));
+        }
+        preprocessed
+    }
+
+    /// The interrupt for a query starting now.
+    fn interrupt(&self) -> Interrupt {
+        let mut interrupt = Interrupt::new();
+        if let Some(budget) = self.time_budget {
+            interrupt = interrupt.deadline(Instant::now() + budget);
+        }
+        if let Some(flag) = &self.cancel_flag {
+            interrupt = interrupt.cancel_flag(flag.clone());
+        }
+        interrupt
+    }
+
+    fn record(&self, ctx: &QueryContext) {
+        if self.collect_stats {
+            *self.last_stats.lock().unwrap_or_else(|e| e.into_inner()) = ctx.stats;
+        }
+    }
+}
+
+/// How well an item matched in [`Matcher::best_match_display`], by the
+/// stage that accepted it.
+enum DisplayScore {
+    /// The score under the profile, and the length in chars.
+    Profile(i64, usize),
+    Tier(Rank),
+    /// The similarity under the fallback metric.
+    Fallback(f64),
+}
+
+impl DisplayScore {
+    /// Whether `self` beats `other`, which was scored earlier.
+    fn beats(&self, other: &DisplayScore) -> bool {
+        match (self, other) {
+            (DisplayScore::Profile(s, l), DisplayScore::Profile(t, m)) => {
+                s > t || (s == t && l < m)
+            }
+            (DisplayScore::Profile(..), _) => true,
+            (DisplayScore::Tier(a), DisplayScore::Tier(b)) => a < b,
+            (DisplayScore::Tier(_), DisplayScore::Fallback(_)) => true,
+            (DisplayScore::Fallback(a), DisplayScore::Fallback(b)) => a > b,
+            _ => false,
+        }
+    }
+}
+
+/// A candidate compared by another form of it, such as its preprocessed or
+/// formatted one.
+#[derive(Clone)]
+struct Preprocessed<T>(String, T);
+
+impl<T> AsRef<str> for Preprocessed<T> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A builder for [`Matcher`].
+#[derive(Clone, Debug, Default)]
+pub struct MatcherBuilder {
+    pipeline: Pipeline,
+    profile: Profile,
+    string_metric: Option<CustomMetric>,
+    fallback: Option<Fallback>,
+    #[cfg(feature = "unicode-normalization")]
+    normalization: Option<Normalization>,
+    ignore_diacritics: bool,
+    ignore_case: bool,
+    snake_case: bool,
+    collect_stats: bool,
+    time_budget: Option<Duration>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl MatcherBuilder {
+    /// Sets the maximum allowable edit distance, which otherwise defaults to
+    /// one-third of the lookup.
+    pub fn max_dist(mut self, max_dist: usize) -> Self {
+        self.pipeline.threshold = Threshold::Absolute(max_dist);
+        self
+    }
+
+    /// Sets how the maximum allowable edit distance follows from the
+    /// lookup, which defaults to [`Threshold::OneThird`].
+    pub fn threshold(mut self, threshold: Threshold) -> Self {
+        self.pipeline.threshold = threshold;
+        self
+    }
+
+    /// Makes the first tier require an exact match instead of one ignoring
+    /// case, so that every tier compares case-sensitively.
+    pub fn case_sensitive(mut self, yes: bool) -> Self {
+        self.pipeline.case_sensitive = yes;
+        self
+    }
+
+    /// Selects how the first tier folds case, which defaults to
+    /// [`CaseFolding::Unicode`].
+    pub fn case_folding(mut self, folding: CaseFolding) -> Self {
+        self.pipeline.case_folding = folding;
+        self
+    }
+
+    /// Turns `tier` on or off; all tiers are on by default.
+    ///
+    /// For case-sensitive names such as shell commands, turning off
+    /// [`Tier::CaseInsensitive`] and [`Tier::SortedWords`] leaves only
+    /// suggestions within the edit distance.
+    pub fn tier(mut self, tier: Tier, enabled: bool) -> Self {
+        self.pipeline.set_enabled(tier, enabled);
+        self
+    }
+
+    /// Selects where the sorted-words tier splits names into words, which
+    /// defaults to underscores only.
+    pub fn word_split(mut self, split: WordSplit) -> Self {
+        self.pipeline.word_split = split;
+        self
+    }
+
+    /// Forgives up to `bonus` edits in the Levenshtein tier for the chars
+    /// a candidate shares as a prefix with the lookup, so that candidates
+    /// completing the lookup rank higher and may exceed the maximum distance
+    /// by up to `bonus`. With a bonus of 2, "print" accepts "println".
+    pub fn prefix_bonus(mut self, bonus: usize) -> Self {
+        self.pipeline.prefix_bonus = bonus;
+        self
+    }
+
+    /// Skips the Levenshtein distance of candidates that share too few
+    /// `n`-character n-grams with the lookup to be within the maximum
+    /// distance, see [`ngram_similarity`](crate::ngram_similarity).
+    ///
+    /// The check is linear in the lengths of the strings, so it pays off
+    /// for long candidates such as titles or log lines, and never rejects a
+    /// candidate that would have matched. Trigrams are a good default; zero
+    /// turns the pre-filter off. Only applies to [`Metric::Levenshtein`].
+    pub fn ngram_prefilter(mut self, n: usize) -> Self {
+        self.pipeline.ngram_prefilter = n;
+        self
+    }
+
+    /// Ranks candidates in the Levenshtein tier by `metric` instead,
+    /// accepting those at most `max_distance` away from the lookup, for a
+    /// domain-specific distance. Overrides [`MatcherBuilder::metric`],
+    /// [`MatcherBuilder::threshold`] and [`MatcherBuilder::prefix_bonus`].
+    pub fn string_metric<M>(mut self, metric: M, max_distance: f64) -> Self
+    where
+        M: StringMetric + Send + Sync + 'static,
+    {
+        self.string_metric = Some(CustomMetric {
+            metric: Arc::new(metric),
+            max_distance,
+        });
+        self
+    }
+
+    /// Selects the edit distance of the Levenshtein tier, which defaults to
+    /// [`Metric::Levenshtein`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.pipeline.metric = metric;
+        self
+    }
+
+    /// Selects how candidates are ranked, which defaults to
+    /// [`Profile::Default`].
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Selects which of several equally good candidates wins, which defaults
+    /// to [`TieBreak::Compatible`].
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.pipeline.tie_break = tie_break;
+        self
+    }
+
+    /// Re-scores candidates with `fallback` when no tier accepts any of
+    /// them, instead of giving up.
+    pub fn fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Normalizes the lookup and every candidate to `form` before comparing
+    /// them.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalization(mut self, form: Normalization) -> Self {
+        self.normalization = Some(form);
+        self
+    }
+
+    /// Compares the lookup and candidates without their diacritics, so that
+    /// "resume" matches "résumé" exactly.
+    pub fn ignore_diacritics(mut self, yes: bool) -> Self {
+        self.ignore_diacritics = yes;
+        self
+    }
+
+    /// Folds the case of the lookup and candidates before comparing them,
+    /// so that every tier ignores case and "Read_File" is at distance 0 from
+    /// "read_file". Overrides [`MatcherBuilder::case_sensitive`].
+    ///
+    /// Case is folded as selected by [`MatcherBuilder::case_folding`];
+    /// [`CaseFolding::Custom`] leaves it as is.
+    pub fn ignore_case(mut self, yes: bool) -> Self {
+        self.ignore_case = yes;
+        self
+    }
+
+    /// Converts the lookup and candidates to `snake_case` with
+    /// [`to_snake_case`] before comparing them, so that identifiers match
+    /// across naming conventions and `maxRetryCount` suggests
+    /// `max_retry_count`.
+    pub fn normalize_identifiers(mut self, yes: bool) -> Self {
+        self.snake_case = yes;
+        self
+    }
+
+    /// Enables recording [`QueryStats`] for every query.
+    pub fn collect_stats(mut self, yes: bool) -> Self {
+        self.collect_stats = yes;
+        self
+    }
+
+    /// Limits every query to `budget`, after which it returns the best match
+    /// found so far.
+    pub fn with_deadline(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Makes every query give up as soon as `flag` is set.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    /// Builds the matcher.
+    pub fn build(self) -> Matcher {
+        Matcher {
+            pipeline: self.pipeline,
+            profile: self.profile,
+            string_metric: self.string_metric,
+            fallback: self.fallback,
+            #[cfg(feature = "unicode-normalization")]
+            normalization: self.normalization,
+            ignore_diacritics: self.ignore_diacritics,
+            ignore_case: self.ignore_case,
+            snake_case: self.snake_case,
+            collect_stats: self.collect_stats,
+            time_budget: self.time_budget,
+            cancel_flag: self.cancel_flag,
+            last_stats: Mutex::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_match() {
+        let input = ["aaab", "aaabc"];
+        let matcher = Matcher::default();
+        assert_eq!(
+            matcher.best_match(input.iter(), "aaaa"),
+            Some("aaab".to_string())
+        );
+        assert_eq!(matcher.best_match(input.iter(), "1111111111"), None);
+
+        let matcher = Matcher::builder().max_dist(0).build();
+        assert_eq!(matcher.best_match(input.iter(), "aaaa"), None);
+    }
+
+    #[test]
+    fn test_ide_profile() {
+        let input = [
+            "src/lib.rs",
+            "src/matcher.rs",
+            "tests/matcher_alloc.rs",
+            "getFooBar",
+        ];
+        let matcher = Matcher::builder().profile(Profile::IdeSymbols).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "mat"),
+            Some("src/matcher.rs".to_string())
+        );
+        assert_eq!(
+            matcher.best_match(input.iter(), "gfb"),
+            Some("getFooBar".to_string())
+        );
+        // No subsequence hit, so typos are corrected as usual.
+        assert_eq!(
+            matcher.best_match(input.iter(), "getFooBra"),
+            Some("getFooBar".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sublime_profile() {
+        let input = ["xaxb_ab", "a_b", "axxxb"];
+        let matcher = Matcher::builder().profile(Profile::Sublime).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "ab"),
+            Some("a_b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_best_match_display() {
+        #[derive(Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+        }
+
+        impl fmt::Display for Color {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(match self {
+                    Color::Red => "red",
+                    Color::Green => "green",
+                })
+            }
+        }
+
+        let matcher = Matcher::default();
+        let colors = || [Color::Red, Color::Green];
+        assert_eq!(
+            matcher.best_match_display(colors(), "gren"),
+            Some(Color::Green)
+        );
+        assert_eq!(
+            matcher.best_match_display(colors(), "RED"),
+            Some(Color::Red)
+        );
+        assert_eq!(matcher.best_match_display(colors(), "blue"), None);
+        assert_eq!(matcher.best_match_display(1000..1100, "1O42"), Some(1042));
+        // An exact match wins over an earlier case-insensitive one.
+        assert_eq!(
+            matcher.best_match_display(["PRINT", "print"], "print"),
+            Some("print")
+        );
+    }
+
+    #[test]
+    fn test_best_match_index() {
+        let input = ["b_a_c", "aaab", "c_b_a"];
+        let matcher = Matcher::default();
+        assert_eq!(matcher.best_match_index(input.iter(), "aaaa"), Some(1));
+        assert_eq!(matcher.best_match_index(input.iter(), "a_b_c"), Some(2));
+
+        let matcher = Matcher::builder().tie_break(TieBreak::Earliest).build();
+        assert_eq!(matcher.best_match_index(input.iter(), "a_b_c"), Some(0));
+        assert_eq!(matcher.best_match_display(input, "a_b_c"), Some("b_a_c"));
+    }
+
+    #[test]
+    fn test_fallback() {
+        let input = ["request_timeout_ms", "max_retries"];
+        let matcher = Matcher::default();
+        assert_eq!(matcher.best_match(input.iter(), "timeout_request"), None);
+
+        let matcher = Matcher::builder()
+            .fallback(Fallback::TokenSetRatio(0.8))
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "timeout_request"),
+            Some("request_timeout_ms".to_string())
+        );
+        assert_eq!(
+            matcher.best_match_display(input, "timeout_request"),
+            Some("request_timeout_ms")
+        );
+
+        let matcher = Matcher::builder()
+            .fallback(Fallback::JaroWinkler(0.8))
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "max_retry_count"),
+            Some("max_retries".to_string())
+        );
+        assert_eq!(matcher.best_match(input.iter(), "zzz"), None);
+
+        let messages = ["permission denied: file", "file not found"];
+        let split = WordSplit::new().whitespace(true);
+        let matcher = Matcher::builder()
+            .fallback(Fallback::TokenJaccard(split, 0.5))
+            .build();
+        assert_eq!(
+            matcher.best_match(messages.iter(), "not found file"),
+            Some("file not found".to_string())
+        );
+        assert_eq!(matcher.best_match(messages.iter(), "disk full"), None);
+
+        let names = ["Jones", "Smythe", "Smyth"];
+        let matcher = Matcher::builder().max_dist(0);
+        assert_eq!(
+            matcher.clone().build().best_match(names.iter(), "Smith"),
+            None
+        );
+        let matcher = matcher.fallback(Fallback::Soundex).build();
+        assert_eq!(
+            matcher.best_match(names.iter(), "Smith"),
+            Some("Smyth".to_string())
+        );
+        assert_eq!(matcher.best_match(names.iter(), "123"), None);
+
+        let names = ["Jones", "Filip"];
+        let matcher = Matcher::builder().max_dist(0);
+        let soundex = matcher.clone().fallback(Fallback::Soundex).build();
+        assert_eq!(soundex.best_match(names.iter(), "Philip"), None);
+        let metaphone = matcher.fallback(Fallback::DoubleMetaphone).build();
+        assert_eq!(
+            metaphone.best_match(names.iter(), "Philip"),
+            Some("Filip".to_string())
+        );
+    }
+
+    #[test]
+    fn test_metric() {
+        let input = ["the", "then"];
+        assert_eq!(Matcher::default().best_match(input.iter(), "teh"), None);
+
+        let matcher = Matcher::builder()
+            .metric(Metric::DamerauLevenshtein)
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "teh"),
+            Some("the".to_string())
+        );
+        assert_eq!(matcher.best_match_display(input, "teh"), Some("the"));
+    }
+
+    #[test]
+    fn test_string_metric() {
+        let input = ["sku-1042", "sku-1024", "sku-2048"];
+        let digits = |a: &str, b: &str| {
+            let digits = |s: &str| s.chars().filter(char::is_ascii_digit).collect::<Vec<_>>();
+            let (mut a, mut b) = (digits(a), digits(b));
+            a.sort_unstable();
+            b.sort_unstable();
+            if a == b {
+                0.5
+            } else {
+                f64::INFINITY
+            }
+        };
+        let matcher = Matcher::builder().string_metric(digits, 1.0).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "SKU-2401"),
+            Some("sku-1042".to_string())
+        );
+        assert_eq!(
+            matcher.best_match_display(input, "sku-4201"),
+            Some("sku-1042")
+        );
+        assert_eq!(
+            matcher.best_match(input.iter(), "SKU-2048"),
+            Some("sku-2048".to_string())
+        );
+        assert_eq!(matcher.best_match(input.iter(), "sku-9999"), None);
+    }
+
+    #[test]
+    fn test_case_sensitive_and_tiers() {
+        let input = ["AAAA", "b_a"];
+        let matcher = Matcher::builder().case_sensitive(true).build();
+        assert_eq!(matcher.best_match(input.iter(), "aaaa"), None);
+        assert_eq!(
+            matcher.best_match(input.iter(), "AAAA"),
+            Some("AAAA".to_string())
+        );
+
+        let matcher = Matcher::builder().tier(Tier::SortedWords, false).build();
+        assert_eq!(matcher.best_match(input.iter(), "a_b"), None);
+        assert_eq!(matcher.best_match_display(input, "a_b"), None);
+        assert_eq!(
+            matcher.best_match(input.iter(), "aaaa"),
+            Some("AAAA".to_string())
+        );
+
+        let matcher = Matcher::builder()
+            .tier(Tier::CaseInsensitive, false)
+            .tier(Tier::Levenshtein, false)
+            .build();
+        assert_eq!(matcher.best_match(input.iter(), "aaaa"), None);
+        assert_eq!(
+            matcher.best_match(input.iter(), "b_a"),
+            Some("b_a".to_string())
+        );
+        assert_eq!(matcher.best_match_display(input, "a_b"), Some("b_a"));
+
+        let names = ["read_file_contents", "read_files"];
+        let matcher = Matcher::builder().threshold(Threshold::Ratio(0.1)).build();
+        assert_eq!(
+            matcher.best_match(names.iter(), "read_file_content"),
+            Some("read_file_contents".to_string())
+        );
+        assert_eq!(matcher.best_match(names.iter(), "read_fil"), None);
+        assert_eq!(
+            Matcher::default().best_match(names.iter(), "read_fil"),
+            Some("read_files".to_string())
+        );
+        let names = ["read_file", "load_file"];
+        let matcher = Matcher::builder().threshold(Threshold::Adaptive).build();
+        assert_eq!(matcher.best_match(names.iter(), "rexd_fxle"), None);
+        assert_eq!(
+            matcher.best_match(names.iter(), "read_fil"),
+            Some("read_file".to_string())
+        );
+
+        let flags = ["--dry-run", "--force"];
+        let matcher = Matcher::builder()
+            .word_split(WordSplit::new().hyphen(true))
+            .build();
+        assert_eq!(
+            matcher.best_match(flags.iter(), "--run-dry"),
+            Some("--dry-run".to_string())
+        );
+        assert_eq!(
+            Matcher::default().best_match(flags.iter(), "--run-dry"),
+            None
+        );
+
+        let commands = ["LS", "ls_l", "cd"];
+        let matcher = Matcher::builder()
+            .tier(Tier::CaseInsensitive, false)
+            .tier(Tier::SortedWords, false)
+            .build();
+        assert_eq!(matcher.best_match(commands.iter(), "ls"), None);
+        assert_eq!(matcher.best_match(commands.iter(), "l_ls"), None);
+        assert_eq!(
+            matcher.best_match(commands.iter(), "cdd"),
+            Some("cd".to_string())
+        );
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn test_normalization() {
+        let input = ["cafe\u{301}", "cafes"];
+        let lookup = "caf\u{e9}s";
+        assert_eq!(
+            Matcher::default().best_match(input.iter(), lookup),
+            Some("cafes".to_string())
+        );
+
+        let matcher = Matcher::builder().normalization(Normalization::Nfc).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), lookup),
+            Some("cafe\u{301}".to_string())
+        );
+        assert_eq!(
+            matcher.best_match_display(input, lookup),
+            Some("cafe\u{301}")
+        );
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let input = ["diyarbakır", "DIYARBAKIR"];
+        let matcher = Matcher::builder().max_dist(0).build();
+        assert_eq!(matcher.best_match(input.iter(), "DİYARBAKIR"), None);
+
+        let matcher = Matcher::builder()
+            .max_dist(0)
+            .case_folding(CaseFolding::Turkic)
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "DİYARBAKIR"),
+            Some("diyarbakır".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ignore_diacritics() {
+        let input = ["résumé", "resumes"];
+        let matcher = Matcher::builder().max_dist(0).build();
+        assert_eq!(matcher.best_match(input.iter(), "resume"), None);
+
+        let matcher = Matcher::builder()
+            .max_dist(0)
+            .ignore_diacritics(true)
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "resume"),
+            Some("résumé".to_string())
+        );
+        assert_eq!(matcher.best_match_display(input, "RESUME"), Some("résumé"));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let input = ["Read_File", "write_file"];
+        let matcher = Matcher::builder()
+            .max_dist(0)
+            .tier(Tier::CaseInsensitive, false);
+        assert_eq!(
+            matcher
+                .clone()
+                .build()
+                .best_match(input.iter(), "read_file"),
+            None
+        );
+
+        let matcher = matcher.ignore_case(true).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "read_file"),
+            Some("Read_File".to_string())
+        );
+        assert_eq!(
+            matcher.best_match_display(input, "FILE_WRITE"),
+            Some("write_file")
+        );
+
+        let matcher = Matcher::builder()
+            .ignore_case(true)
+            .case_sensitive(true)
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "READ_FILE"),
+            Some("Read_File".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_identifiers() {
+        let input = ["max_retry_count", "max_retries"];
+        assert_eq!(
+            Matcher::default().best_match(input.iter(), "MaxRetryCount"),
+            None
+        );
+
+        let matcher = Matcher::builder().normalize_identifiers(true).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "MaxRetryCount"),
+            Some("max_retry_count".to_string())
+        );
+        assert_eq!(
+            matcher.best_match_display(input, "MaxRetries"),
+            Some("max_retries")
+        );
+    }
+
+    #[test]
+    fn test_prefix_bonus() {
+        let input = ["sprint", "println"];
+        assert_eq!(
+            Matcher::default().best_match(input.iter(), "print"),
+            Some("sprint".to_string())
+        );
+
+        let matcher = Matcher::builder().prefix_bonus(2).build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "print"),
+            Some("println".to_string())
+        );
+        assert_eq!(matcher.best_match_display(input, "print"), Some("println"));
+        assert_eq!(matcher.best_match(input.iter(), "xyz"), None);
+    }
+
+    #[test]
+    fn test_ngram_prefilter() {
+        let input = [
+            "connection refused by remote host",
+            "connection reset by the peer host",
+            "permission denied for these users",
+        ];
+        let lookup = "connection refused by remote hots";
+        let plain = Matcher::builder().max_dist(3).collect_stats(true).build();
+        let filtered = Matcher::builder()
+            .max_dist(3)
+            .ngram_prefilter(3)
+            .collect_stats(true)
+            .build();
+        assert_eq!(
+            filtered.best_match(input.iter(), lookup),
+            Some(input[0].to_string())
+        );
+        plain.best_match(input.iter(), lookup);
+        assert_eq!(filtered.stats().early_exits, 2);
+        assert!(filtered.stats().cells_computed < plain.stats().cells_computed);
+    }
+
+    #[test]
+    fn test_stats() {
+        let input = ["print", "println", "format", "a_much_longer_name"];
+        let matcher = Matcher::builder().collect_stats(true).build();
+        matcher.best_match(input.iter(), "prinr");
+        let stats = matcher.stats();
+        assert_eq!(stats.candidates_scanned, 4);
+        assert_eq!(stats.early_exits, 2);
+        assert!(stats.cells_computed > 0);
+
+        let matcher = Matcher::default();
+        matcher.best_match(input.iter(), "prnt");
+        assert_eq!(matcher.stats(), QueryStats::default());
+    }
+
+    #[test]
+    fn test_deadline() {
+        let input = ["aaab", "aaabc"];
+        let matcher = Matcher::builder()
+            .with_deadline(Duration::ZERO)
+            .collect_stats(true)
+            .build();
+        assert_eq!(matcher.best_match(input.iter(), "aaaa"), None);
+        assert!(matcher.stats().timed_out);
+
+        let matcher = Matcher::builder().collect_stats(true).build();
+        let later = Interrupt::new().deadline(Instant::now() + Duration::from_secs(60));
+        assert_eq!(
+            matcher.best_match_interruptible(input.iter(), "aaaa", &later),
+            Some("aaab".to_string())
+        );
+        assert!(!matcher.stats().timed_out);
+    }
+
+    #[test]
+    fn test_cancel_flag() {
+        let input = ["aaab", "aaabc"];
+        let flag = Arc::new(AtomicBool::new(false));
+        let matcher = Matcher::builder()
+            .cancel_flag(flag.clone())
+            .collect_stats(true)
+            .build();
+        assert_eq!(
+            matcher.best_match(input.iter(), "aaaa"),
+            Some("aaab".to_string())
+        );
+        assert!(!matcher.stats().cancelled);
+
+        flag.store(true, Ordering::Relaxed);
+        assert_eq!(matcher.best_match(input.iter(), "aaaa"), None);
+        assert!(matcher.stats().cancelled);
+    }
+}
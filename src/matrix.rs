@@ -0,0 +1,129 @@
+//! Pairwise distance matrices.
+
+use std::ops::Index;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::kernel::{self, PatternMasks};
+
+/// The distances between every row string and every column string, stored
+/// flat in row-major order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistanceMatrix {
+    cols: usize,
+    data: Vec<usize>,
+}
+
+impl DistanceMatrix {
+    /// The number of rows.
+    pub fn rows(&self) -> usize {
+        self.data.len().checked_div(self.cols).unwrap_or(0)
+    }
+
+    /// The number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The distance between row `i` and column `j`.
+    pub fn get(&self, i: usize, j: usize) -> Option<usize> {
+        (j < self.cols).then(|| self.data.get(i * self.cols + j).copied())?
+    }
+
+    /// The distances from row `i` to every column.
+    pub fn row(&self, i: usize) -> &[usize] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// The distances in row-major order.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.data
+    }
+
+    /// Converts the matrix into one `Vec` per row.
+    pub fn to_vecs(&self) -> Vec<Vec<usize>> {
+        (0..self.rows()).map(|i| self.row(i).to_vec()).collect()
+    }
+}
+
+impl Index<(usize, usize)> for DistanceMatrix {
+    type Output = usize;
+
+    fn index(&self, (i, j): (usize, usize)) -> &usize {
+        assert!(j < self.cols, "column {} out of bounds", j);
+        &self.data[i * self.cols + j]
+    }
+}
+
+/// Finds the Levenshtein distance between every string of `rows` and every
+/// string of `cols`.
+///
+/// Every string is decoded once rather than once per pair, and each row is
+/// prepared once for the bit-parallel kernel when it is short enough. With
+/// the `parallel` feature, rows are computed in parallel.
+pub fn lev_distance_matrix<R, C>(rows: &[R], cols: &[C]) -> DistanceMatrix
+where
+    R: AsRef<str> + Sync,
+    C: AsRef<str> + Sync,
+{
+    let decoded: Vec<Vec<char>> = cols.iter().map(|c| c.as_ref().chars().collect()).collect();
+    let mut data = vec![0; rows.len() * cols.len()];
+    if !cols.is_empty() {
+        #[cfg(feature = "parallel")]
+        let chunks = data.par_chunks_mut(cols.len()).zip(rows);
+        #[cfg(not(feature = "parallel"))]
+        let chunks = data.chunks_mut(cols.len()).zip(rows);
+        chunks.for_each(|(out, row)| fill_row(row.as_ref(), &decoded, out));
+    }
+    DistanceMatrix {
+        cols: cols.len(),
+        data,
+    }
+}
+
+/// Writes the distances from `row` to each of `cols` into `out`.
+fn fill_row(row: &str, cols: &[Vec<char>], out: &mut [usize]) {
+    let pattern: Vec<char> = row.chars().collect();
+    if pattern.len() <= kernel::MYERS_MAX_LEN {
+        let masks = PatternMasks::new(&pattern);
+        for (dist, col) in out.iter_mut().zip(cols) {
+            *dist = kernel::myers(pattern.len(), col.iter().map(|&c| masks.get(c)));
+        }
+    } else {
+        for (dist, col) in out.iter_mut().zip(cols) {
+            *dist = kernel::slice(&pattern, col);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_lev_distance_matrix() {
+        let long = "ab".repeat(40);
+        let rows = ["kitten", "", "größe", long.as_str()];
+        let cols = ["sitting", "grosse", "", "abab"];
+        let matrix = lev_distance_matrix(&rows, &cols);
+        assert_eq!((matrix.rows(), matrix.cols()), (4, 4));
+        for (i, a) in rows.iter().enumerate() {
+            for (j, b) in cols.iter().enumerate() {
+                assert_eq!(matrix[(i, j)], lev_distance(a, b), "{:?} {:?}", a, b);
+            }
+        }
+        assert_eq!(matrix.row(0), &[3, 6, 6, 6]);
+        assert_eq!(matrix.get(0, 4), None);
+        assert_eq!(matrix.to_vecs()[2], matrix.row(2));
+    }
+
+    #[test]
+    fn test_empty() {
+        let none: [&str; 0] = [];
+        assert_eq!(lev_distance_matrix(&none, &["a"]).rows(), 0);
+        let matrix = lev_distance_matrix(&["a", "b"], &none);
+        assert_eq!((matrix.rows(), matrix.cols()), (0, 0));
+    }
+}
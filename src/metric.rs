@@ -0,0 +1,277 @@
+//! The edit distances the matchers can rank candidates by.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::matcher::QueryContext;
+use crate::{
+    damerau, find_best_match_in_context, jaro_winkler, kernel, lev_similarity, Pipeline, QueryStats,
+};
+
+/// The edit distance used by the Levenshtein tier of a
+/// [`Matcher`](crate::Matcher).
+///
+/// Non-exhaustive, as some variants depend on crate features.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Metric {
+    /// The Levenshtein distance, as in
+    /// [`find_best_match_for_name`](crate::find_best_match_for_name).
+    #[default]
+    Levenshtein,
+    /// The Damerau–Levenshtein distance, which forgives adjacent swaps such
+    /// as "teh" for "the".
+    DamerauLevenshtein,
+    /// The Levenshtein distance over extended grapheme clusters, see
+    /// [`grapheme_lev_distance`](crate::grapheme_lev_distance).
+    #[cfg(feature = "unicode-segmentation")]
+    Graphemes,
+}
+
+impl Metric {
+    /// Computes the distance between `a` and `b`, returning some value
+    /// greater than `limit` if it obviously exceeds it.
+    pub(crate) fn distance_counted(
+        self,
+        a: &str,
+        b: &str,
+        limit: Option<usize>,
+        stats: &mut QueryStats,
+    ) -> usize {
+        match self {
+            Metric::Levenshtein => kernel::distance_counted(a, b, limit, stats),
+            Metric::DamerauLevenshtein => damerau::distance_counted(a, b, limit, stats),
+            #[cfg(feature = "unicode-segmentation")]
+            Metric::Graphemes => crate::grapheme::distance_counted(a, b, limit, stats),
+        }
+    }
+}
+
+/// A distance between strings that candidates can be ranked by, in place of
+/// the Levenshtein distance, with [`find_best_match_by_metric`] or
+/// [`MatcherBuilder::string_metric`](crate::MatcherBuilder::string_metric).
+///
+/// Implemented by [`Metric`], [`JaroWinklerDistance`] and closures, so that
+/// a domain-specific distance plugs into the matching pipeline.
+pub trait StringMetric {
+    /// The distance between `a` and `b`, where `0.0` means equal and larger
+    /// means further apart.
+    fn distance(&self, a: &str, b: &str) -> f64;
+
+    /// Like [`StringMetric::distance`], returning some value greater than
+    /// `limit` as soon as the distance is known to exceed it, and counting
+    /// the work done in `stats`.
+    ///
+    /// Defaults to the full distance.
+    fn distance_within(&self, a: &str, b: &str, limit: f64, stats: &mut QueryStats) -> f64 {
+        let _ = (limit, stats);
+        self.distance(a, b)
+    }
+}
+
+impl StringMetric for Metric {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        self.distance_counted(a, b, None, &mut QueryStats::default()) as f64
+    }
+
+    fn distance_within(&self, a: &str, b: &str, limit: f64, stats: &mut QueryStats) -> f64 {
+        self.distance_counted(a, b, Some(limit as usize), stats) as f64
+    }
+}
+
+/// One minus the [`jaro_winkler`] similarity, in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JaroWinklerDistance;
+
+impl StringMetric for JaroWinklerDistance {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        1.0 - jaro_winkler(a, b)
+    }
+}
+
+impl<F> StringMetric for F
+where
+    F: Fn(&str, &str) -> f64,
+{
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        self(a, b)
+    }
+}
+
+/// One minus the [`lev_similarity`], the Levenshtein distance relative to
+/// the longer string, in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NormalizedLevenshtein;
+
+impl StringMetric for NormalizedLevenshtein {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        1.0 - lev_similarity(a, b)
+    }
+}
+
+/// One minus the share of the longer string taken up by the prefix both
+/// strings have, in `0.0..=1.0`, so that completions of a lookup rank
+/// closer the less they add to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrefixDistance;
+
+impl StringMetric for PrefixDistance {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        let len = a.chars().count().max(b.chars().count());
+        if len == 0 {
+            return 0.0;
+        }
+        let prefix = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+        1.0 - prefix as f64 / len as f64
+    }
+}
+
+/// A weighted sum of [`StringMetric`]s, ranking candidates by several
+/// criteria at once so that one of them breaks the ties of another.
+///
+/// ```
+/// use lev_distance::{CompositeMetric, Matcher, NormalizedLevenshtein, PrefixDistance};
+///
+/// let metric = CompositeMetric::new()
+///     .weighted(0.7, NormalizedLevenshtein)
+///     .weighted(0.3, PrefixDistance);
+/// let matcher = Matcher::builder().string_metric(metric, 0.5).build();
+/// assert_eq!(
+///     matcher.best_match(["bat", "cap"].iter(), "cat"),
+///     Some("cap".to_string())
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct CompositeMetric {
+    parts: Vec<(f64, Arc<dyn StringMetric + Send + Sync>)>,
+}
+
+impl CompositeMetric {
+    /// Creates a metric with no parts, under which all strings are equal.
+    pub fn new() -> Self {
+        CompositeMetric::default()
+    }
+
+    /// Adds `metric` with the given weight.
+    pub fn weighted<M>(mut self, weight: f64, metric: M) -> Self
+    where
+        M: StringMetric + Send + Sync + 'static,
+    {
+        self.parts.push((weight, Arc::new(metric)));
+        self
+    }
+}
+
+impl StringMetric for CompositeMetric {
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        self.parts
+            .iter()
+            .map(|(weight, metric)| weight * metric.distance(a, b))
+            .sum()
+    }
+}
+
+impl fmt::Debug for CompositeMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weights: Vec<f64> = self.parts.iter().map(|&(weight, _)| weight).collect();
+        f.debug_struct("CompositeMetric")
+            .field("weights", &weights)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`StringMetric`] chosen for a [`Matcher`](crate::Matcher), with the
+/// largest distance it accepts.
+#[derive(Clone)]
+pub(crate) struct CustomMetric {
+    pub(crate) metric: Arc<dyn StringMetric + Send + Sync>,
+    pub(crate) max_distance: f64,
+}
+
+impl fmt::Debug for CustomMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomMetric")
+            .field("max_distance", &self.max_distance)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Finds the best match for a given word in the given iterator, ranking by
+/// `metric` instead of the Levenshtein distance.
+///
+/// Candidates need a distance of at most `max_distance`; the closest one
+/// wins, the earliest on ties. Exact case-insensitive and sorted-word
+/// matches are found as in
+/// [`find_best_match_for_name`](crate::find_best_match_for_name).
+pub fn find_best_match_by_metric<T, M>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    metric: &M,
+    max_distance: f64,
+) -> Option<String>
+where
+    T: AsRef<str>,
+    M: StringMetric + ?Sized,
+{
+    find_best_match_in_context(
+        iter_names,
+        lookup,
+        &Pipeline::default(),
+        metric,
+        max_distance,
+        &mut QueryContext::default(),
+    )
+    .map(|(candidate, _)| candidate.as_ref().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_best_match_by_metric() {
+        let input = ["the", "then", "Foo_Bar", "bar_foo"];
+        let by = |lookup, metric: &dyn StringMetric, max| {
+            find_best_match_by_metric(input.iter(), lookup, metric, max)
+        };
+        assert_eq!(by("teh", &Metric::Levenshtein, 1.0), None);
+        assert_eq!(
+            by("teh", &Metric::DamerauLevenshtein, 1.0),
+            Some("the".to_string())
+        );
+        assert_eq!(
+            by("thenn", &JaroWinklerDistance, 0.1),
+            Some("then".to_string())
+        );
+        assert_eq!(
+            by("foo_bar", &JaroWinklerDistance, 0.0),
+            Some("Foo_Bar".to_string())
+        );
+        assert_eq!(
+            by("foo_bar", &|_: &str, _: &str| 1.0, 0.5),
+            Some("Foo_Bar".to_string())
+        );
+        let by_len = |a: &str, b: &str| a.len().abs_diff(b.len()) as f64;
+        assert_eq!(by("abcd", &by_len, 0.0), Some("then".to_string()));
+        assert_eq!(by("zzzzzzzzzz", &by_len, 1.0), None);
+    }
+
+    #[test]
+    fn test_composite_metric() {
+        assert_eq!(PrefixDistance.distance("print", "println"), 1.0 - 5.0 / 7.0);
+        assert_eq!(PrefixDistance.distance("", ""), 0.0);
+        assert!((NormalizedLevenshtein.distance("cat", "bat") - 1.0 / 3.0).abs() < 1e-9);
+
+        let input = ["bat", "cap"];
+        let plain = find_best_match_by_metric(input.iter(), "cat", &NormalizedLevenshtein, 0.5);
+        assert_eq!(plain, Some("bat".to_string()));
+        let metric = CompositeMetric::new()
+            .weighted(0.7, NormalizedLevenshtein)
+            .weighted(0.3, PrefixDistance);
+        assert_eq!(
+            find_best_match_by_metric(input.iter(), "cat", &metric, 0.5),
+            Some("cap".to_string())
+        );
+        assert_eq!(CompositeMetric::new().distance("a", "b"), 0.0);
+    }
+}
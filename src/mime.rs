@@ -0,0 +1,138 @@
+//! Common IANA media types.
+
+use crate::table::suggest_ignore_ascii_case;
+
+/// The top-level media types registered with IANA.
+const TOP_LEVEL_TYPES: &[&str] = &[
+    "application",
+    "audio",
+    "font",
+    "image",
+    "message",
+    "model",
+    "multipart",
+    "text",
+    "video",
+];
+
+/// Commonly used media types registered with IANA.
+pub const MIME_TYPES: &[&str] = &[
+    "application/gzip",
+    "application/javascript",
+    "application/json",
+    "application/ld+json",
+    "application/msword",
+    "application/octet-stream",
+    "application/ogg",
+    "application/pdf",
+    "application/rtf",
+    "application/vnd.ms-excel",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/wasm",
+    "application/x-www-form-urlencoded",
+    "application/xhtml+xml",
+    "application/xml",
+    "application/yaml",
+    "application/zip",
+    "audio/aac",
+    "audio/flac",
+    "audio/midi",
+    "audio/mpeg",
+    "audio/ogg",
+    "audio/wav",
+    "audio/webm",
+    "font/otf",
+    "font/ttf",
+    "font/woff",
+    "font/woff2",
+    "image/avif",
+    "image/bmp",
+    "image/gif",
+    "image/jpeg",
+    "image/png",
+    "image/svg+xml",
+    "image/tiff",
+    "image/vnd.microsoft.icon",
+    "image/webp",
+    "message/http",
+    "message/rfc822",
+    "model/gltf+json",
+    "model/gltf-binary",
+    "model/obj",
+    "multipart/alternative",
+    "multipart/byteranges",
+    "multipart/form-data",
+    "multipart/mixed",
+    "text/calendar",
+    "text/css",
+    "text/csv",
+    "text/html",
+    "text/javascript",
+    "text/markdown",
+    "text/plain",
+    "text/xml",
+    "video/mp2t",
+    "video/mp4",
+    "video/mpeg",
+    "video/ogg",
+    "video/quicktime",
+    "video/webm",
+];
+
+/// Suggests the media type closest to `s`.
+///
+/// The type and subtype are matched separately, so `aplication/jsn` is
+/// corrected to `application/json` even though the whole string is too far
+/// off. Parameters such as `; charset=utf-8` are ignored. Returns `None` if
+/// `s` is already a known media type or nothing is close enough.
+pub fn suggest_mime(s: &str) -> Option<&'static str> {
+    let essence = s.split(';').next().unwrap_or_default().trim();
+    if MIME_TYPES.iter().any(|m| m.eq_ignore_ascii_case(essence)) {
+        return None;
+    }
+    let (ty, subtype) = match essence.split_once('/') {
+        Some(parts) => parts,
+        None => return suggest_ignore_ascii_case(MIME_TYPES, essence),
+    };
+
+    let ty = match TOP_LEVEL_TYPES.iter().find(|t| t.eq_ignore_ascii_case(ty)) {
+        Some(ty) => *ty,
+        None => suggest_ignore_ascii_case(TOP_LEVEL_TYPES, ty)?,
+    };
+    let subtypes: Vec<&'static str> = MIME_TYPES
+        .iter()
+        .filter_map(|m| m.strip_prefix(ty)?.strip_prefix('/'))
+        .collect();
+    let subtype = match subtypes.iter().find(|t| t.eq_ignore_ascii_case(subtype)) {
+        Some(subtype) => *subtype,
+        None => suggest_ignore_ascii_case(&subtypes, subtype)?,
+    };
+    MIME_TYPES
+        .iter()
+        .find(|m| m.strip_prefix(ty).and_then(|m| m.strip_prefix('/')) == Some(subtype))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_mime() {
+        assert_eq!(suggest_mime("aplication/jsn"), Some("application/json"));
+        assert_eq!(suggest_mime("image/jpg"), Some("image/jpeg"));
+        assert_eq!(suggest_mime("txt/html; charset=utf-8"), Some("text/html"));
+        assert_eq!(suggest_mime("text/htm"), Some("text/html"));
+    }
+
+    #[test]
+    fn test_suggest_mime_none() {
+        assert_eq!(suggest_mime("application/json"), None);
+        assert_eq!(suggest_mime("Text/Plain; charset=utf-8"), None);
+        assert_eq!(suggest_mime("chemical/x-pdb"), None);
+        assert_eq!(suggest_mime("image/unknown"), None);
+    }
+}
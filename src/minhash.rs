@@ -0,0 +1,278 @@
+//! MinHash signatures and LSH blocking for large fuzzy joins.
+//!
+//! Comparing every pair of records is hopeless once both sides have millions
+//! of entries. MinHash estimates the Jaccard similarity of the character
+//! n-gram sets of two strings, and locality-sensitive hashing groups the
+//! signatures into bands so that only strings sharing a band bucket are ever
+//! compared with the exact edit distance. Recall is probabilistic: pairs with
+//! few shared n-grams may be missed.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::hash::{fnv1a, ngram_hashes, splitmix64};
+use crate::kernel;
+use crate::{Interrupt, QueryContext};
+
+/// Computes MinHash signatures over character n-grams.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinHasher {
+    ngram: usize,
+    bands: usize,
+    rows: usize,
+    seeds: Vec<u64>,
+}
+
+impl Default for MinHasher {
+    /// Bigrams with 20 bands of 3 rows, which favours recall on short
+    /// strings.
+    fn default() -> Self {
+        MinHasher::new(2, 20, 3)
+    }
+}
+
+impl MinHasher {
+    /// Creates a hasher over `ngram`-character n-grams producing signatures
+    /// of `bands * rows` values.
+    ///
+    /// More bands raise recall, more rows per band raise precision. Zero
+    /// n-gram sizes, bands or rows are treated as one.
+    pub fn new(ngram: usize, bands: usize, rows: usize) -> Self {
+        let (bands, rows) = (bands.max(1), rows.max(1));
+        let seeds = (0..bands * rows).map(|i| splitmix64(i as u64)).collect();
+        MinHasher {
+            ngram: ngram.max(1),
+            bands,
+            rows,
+            seeds,
+        }
+    }
+
+    /// Computes the MinHash signature of `s`.
+    pub fn signature(&self, s: &str) -> Vec<u64> {
+        let grams = ngram_hashes(s, self.ngram);
+        self.seeds
+            .iter()
+            .map(|&seed| {
+                grams
+                    .iter()
+                    .map(|&h| splitmix64(h ^ seed))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+
+    fn band_keys(&self, s: &str) -> impl Iterator<Item = u64> {
+        let signature = self.signature(s);
+        let rows = self.rows;
+        (0..self.bands).map(move |band| {
+            let values = &signature[band * rows..(band + 1) * rows];
+            fnv1a(values.iter().flat_map(|v| v.to_le_bytes()))
+        })
+    }
+}
+
+/// An LSH index over MinHash signatures.
+#[derive(Clone, Debug)]
+pub struct LshIndex {
+    hasher: MinHasher,
+    items: Vec<String>,
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+impl LshIndex {
+    /// Creates an empty index using `hasher`.
+    pub fn new(hasher: MinHasher) -> Self {
+        let buckets = vec![HashMap::new(); hasher.bands];
+        LshIndex {
+            hasher,
+            items: Vec::new(),
+            buckets,
+        }
+    }
+
+    /// Adds `item` to the index and returns its id.
+    pub fn insert(&mut self, item: &str) -> usize {
+        let id = self.items.len();
+        for (band, key) in self.hasher.band_keys(item).enumerate() {
+            self.buckets[band].entry(key).or_default().push(id);
+        }
+        self.items.push(item.to_string());
+        id
+    }
+
+    /// The item with the given id.
+    pub fn get(&self, id: usize) -> Option<&str> {
+        self.items.get(id).map(String::as_str)
+    }
+
+    /// The ids of all items sharing at least one band bucket with `query`,
+    /// in ascending order.
+    pub fn candidates(&self, query: &str) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .hasher
+            .band_keys(query)
+            .zip(&self.buckets)
+            .filter_map(|(key, buckets)| buckets.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Finds the items within `max_dist` edits of `query` among the
+    /// candidates, as `(id, distance)` pairs in ascending id order.
+    pub fn find_within(&self, query: &str, max_dist: usize) -> Vec<(usize, usize)> {
+        self.find_within_interruptible(query, max_dist, &Interrupt::new())
+    }
+
+    /// Like [`find_within`](LshIndex::find_within), but stops verifying
+    /// candidates once `deadline` has passed and returns the matches found
+    /// so far.
+    pub fn find_within_until(
+        &self,
+        query: &str,
+        max_dist: usize,
+        deadline: Option<Instant>,
+    ) -> Vec<(usize, usize)> {
+        let interrupt = match deadline {
+            Some(deadline) => Interrupt::new().deadline(deadline),
+            None => Interrupt::new(),
+        };
+        self.find_within_interruptible(query, max_dist, &interrupt)
+    }
+
+    /// Like [`find_within`](LshIndex::find_within), but stops verifying
+    /// candidates once `interrupt` fires and returns the matches found so
+    /// far.
+    pub fn find_within_interruptible(
+        &self,
+        query: &str,
+        max_dist: usize,
+        interrupt: &Interrupt,
+    ) -> Vec<(usize, usize)> {
+        let mut ctx = QueryContext::new(interrupt.clone());
+        self.candidates(query)
+            .into_iter()
+            .take_while(|_| !ctx.should_stop())
+            .filter_map(|id| {
+                let dist = kernel::distance(query, &self.items[id], Some(max_dist));
+                (dist <= max_dist).then_some((id, dist))
+            })
+            .collect()
+    }
+}
+
+impl Default for LshIndex {
+    fn default() -> Self {
+        LshIndex::new(MinHasher::default())
+    }
+}
+
+/// Joins two record sets on edit distance, using LSH to pick the pairs worth
+/// comparing.
+///
+/// Returns `(left_index, right_index, distance)` for every verified pair
+/// within `max_dist`, ordered by left index and then right index.
+pub fn fuzzy_join<L, R>(
+    left: &[L],
+    right: &[R],
+    max_dist: usize,
+    hasher: MinHasher,
+) -> Vec<(usize, usize, usize)>
+where
+    L: AsRef<str>,
+    R: AsRef<str>,
+{
+    let mut index = LshIndex::new(hasher);
+    for item in right {
+        index.insert(item.as_ref());
+    }
+    left.iter()
+        .enumerate()
+        .flat_map(|(i, item)| {
+            index
+                .find_within(item.as_ref(), max_dist)
+                .into_iter()
+                .map(move |(j, dist)| (i, j, dist))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        let hasher = MinHasher::default();
+        assert_eq!(hasher.signature("hello").len(), 60);
+        assert_eq!(hasher.signature("hello"), hasher.signature("hello"));
+
+        let matching = |a, b| {
+            let (a, b) = (hasher.signature(a), hasher.signature(b));
+            a.iter().zip(&b).filter(|(x, y)| x == y).count()
+        };
+        assert!(matching("international", "internationl") > matching("international", "zebra"));
+    }
+
+    #[test]
+    fn test_lsh_index() {
+        let mut index = LshIndex::new(MinHasher::default());
+        for item in ["international business", "national bank", "zebra crossing"] {
+            index.insert(item);
+        }
+        assert_eq!(index.find_within("internationl business", 2), vec![(0, 1)]);
+        assert!(!index.candidates("zebra crosing").is_empty());
+        assert_eq!(index.get(2), Some("zebra crossing"));
+        assert_eq!(
+            index.find_within_until("internationl business", 2, Some(Instant::now())),
+            vec![]
+        );
+        assert_eq!(
+            index.find_within_until("internationl business", 2, None),
+            vec![(0, 1)]
+        );
+        assert_eq!(
+            index.find_within_interruptible(
+                "internationl business",
+                2,
+                &Interrupt::new().deadline(Instant::now())
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_zero_bands_or_rows() {
+        for (bands, rows) in [(0, 3), (3, 0), (0, 0)] {
+            let hasher = MinHasher::new(2, bands, rows);
+            assert_eq!(hasher, MinHasher::new(2, bands.max(1), rows.max(1)));
+            let mut index = LshIndex::new(hasher);
+            index.insert("hello");
+            assert_eq!(index.find_within("hello", 0), vec![(0, 0)]);
+        }
+    }
+
+    #[test]
+    fn test_lsh_index_short_strings() {
+        // "ab" is a single n-gram, which must hash like the one it shares
+        // with "abc".
+        let mut index = LshIndex::new(MinHasher::default());
+        index.insert("ab");
+        assert_eq!(index.find_within("abc", 1), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_fuzzy_join() {
+        let left = ["Jonathan Smith", "Mary Johnson", "Unmatched Person"];
+        let right = ["Mary Jonson", "Jonathon Smith", "Someone Else"];
+        assert_eq!(
+            fuzzy_join(&left, &right, 2, MinHasher::default()),
+            vec![(0, 1, 1), (1, 0, 1)]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Character n-gram similarity, which is linear in the lengths of the
+//! strings and so suits long ones such as titles or log lines.
+
+use std::collections::HashMap;
+
+use crate::hash::ngram_hashes;
+
+/// Counts the `n`-character n-grams of `chars`, or `chars` as a whole if it
+/// is non-empty and shorter than `n`.
+fn ngram_counts(chars: &[char], n: usize) -> HashMap<&[char], usize> {
+    let mut counts = HashMap::new();
+    if chars.len() < n {
+        if !chars.is_empty() {
+            counts.insert(chars, 1);
+        }
+        return counts;
+    }
+    for gram in chars.windows(n) {
+        *counts.entry(gram).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The n-grams shared by `a` and `b` and the n-grams either has, counting
+/// repeated n-grams as often as they occur.
+fn ngram_overlap(a: &str, b: &str, n: usize) -> (usize, usize) {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (a, b) = (ngram_counts(&a, n), ngram_counts(&b, n));
+    let shared: usize = a
+        .iter()
+        .map(|(gram, &count)| count.min(b.get(gram).copied().unwrap_or(0)))
+        .sum();
+    let total = a.values().sum::<usize>() + b.values().sum::<usize>() - shared;
+    (shared, total)
+}
+
+/// Finds the similarity of two strings in `0.0..=1.0` as the share of their
+/// `n`-character n-grams they have in common, so `1.0` means the same
+/// n-grams.
+///
+/// Strings shorter than `n` count as a single n-gram, and two empty strings
+/// have a similarity of `1.0`. An `n` of zero is treated as one.
+pub fn ngram_similarity(a: &str, b: &str, n: usize) -> f64 {
+    let (shared, total) = ngram_overlap(a, b, n.max(1));
+    if total == 0 {
+        return 1.0;
+    }
+    shared as f64 / total as f64
+}
+
+/// The [`ngram_similarity`] of two strings over trigrams.
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    ngram_similarity(a, b, 3)
+}
+
+/// Finds the Sørensen–Dice coefficient of two strings in `0.0..=1.0`: twice
+/// the bigrams they share over the bigrams of both, so `1.0` means the same
+/// bigrams.
+///
+/// Forgiving of reordered words, it suits short human-entered phrases.
+/// Single chars count as one bigram, and two empty strings have a
+/// coefficient of `1.0`.
+pub fn dice_coefficient(a: &str, b: &str) -> f64 {
+    let (shared, total) = ngram_overlap(a, b, 2);
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * shared as f64 / (total + shared) as f64
+}
+
+/// Finds the cosine similarity of the `n`-character n-gram frequency
+/// vectors of two strings in `0.0..=1.0`, so `1.0` means the same n-grams in
+/// the same proportions.
+///
+/// The vectors are indexed by n-gram hashes. Since only proportions count,
+/// a string compares equal to itself repeated, which makes the similarity
+/// tolerant of scale, e.g. for deduplicating long titles. Strings no longer
+/// than `n` count as a single n-gram. An `n` of zero is treated as one.
+pub fn ngram_cosine(a: &str, b: &str, n: usize) -> f64 {
+    let vector = |s| {
+        let mut counts: HashMap<u64, f64> = HashMap::new();
+        for hash in ngram_hashes(s, n.max(1)) {
+            *counts.entry(hash).or_insert(0.0) += 1.0;
+        }
+        counts
+    };
+    let (a, b) = (vector(a), vector(b));
+    let norm = |v: &HashMap<u64, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    // Folded from `0.0`, as an empty sum is `-0.0`.
+    let dot = a
+        .iter()
+        .filter_map(|(hash, x)| Some(x * b.get(hash)?))
+        .fold(0.0, |dot, product| dot + product);
+    (dot / (norm(&a) * norm(&b))).clamp(0.0, 1.0)
+}
+
+/// Whether the Levenshtein distance between `a` and `b` may be at most
+/// `max_dist`, judging by their shared `n`-grams.
+///
+/// Each edit destroys at most `n` of the n-grams of the longer string, so
+/// strings within the distance share at least that many fewer than it has.
+/// Never rejects a pair that is within the distance.
+pub(crate) fn may_be_within(a: &str, b: &str, n: usize, max_dist: usize) -> bool {
+    let n = n.max(1);
+    let len = a.chars().count().max(b.chars().count());
+    let Some(required) = (len + 1).checked_sub(max_dist.saturating_add(1).saturating_mul(n)) else {
+        return true;
+    };
+    required == 0 || ngram_overlap(a, b, n).0 >= required
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_ngram_similarity() {
+        assert_eq!(trigram_similarity("hello world", "hello world"), 1.0);
+        assert_eq!(trigram_similarity("abcd", "bcde"), 1.0 / 3.0);
+        assert_eq!(ngram_similarity("abc", "xyz", 2), 0.0);
+        assert_eq!(ngram_similarity("ab", "ab", 3), 1.0);
+        assert_eq!(ngram_similarity("", "", 3), 1.0);
+        assert_eq!(ngram_similarity("", "abc", 0), 0.0);
+        assert!(
+            trigram_similarity("connection refused", "connection reset")
+                > trigram_similarity("connection refused", "permission denied")
+        );
+    }
+
+    #[test]
+    fn test_dice_coefficient() {
+        assert_eq!(dice_coefficient("night", "nacht"), 0.25);
+        assert_eq!(dice_coefficient("abc", "abc"), 1.0);
+        assert_eq!(dice_coefficient("aa", "aaaa"), 0.5);
+        assert_eq!(dice_coefficient("", ""), 1.0);
+        assert_eq!(dice_coefficient("a", ""), 0.0);
+        assert!(dice_coefficient("new york", "york new") > 0.7);
+    }
+
+    #[test]
+    fn test_ngram_cosine() {
+        let title = "Acme 65W USB-C Charger";
+        assert!((ngram_cosine(title, title, 3) - 1.0).abs() < 1e-9);
+        let twice = format!("{} {}", title, title);
+        assert!(ngram_cosine(title, &twice, 3) > 0.9);
+        assert!(
+            ngram_cosine(title, "ACME 65W USB-C charger", 3)
+                > ngram_cosine(title, "Wireless Mouse", 3)
+        );
+        assert_eq!(ngram_cosine("abc", "xyz", 2), 0.0);
+        assert_eq!(ngram_cosine("", "", 3), 1.0);
+        // "abc" is a single trigram, which "abcd" shares.
+        assert!((ngram_cosine("abc", "abcd", 3) - 0.5f64.sqrt()).abs() < 1e-9);
+        assert!(ngram_cosine("abc", "xyz", 3).is_sign_positive());
+    }
+
+    #[test]
+    fn test_may_be_within() {
+        let words = [
+            "kitten",
+            "sitting",
+            "mitten",
+            "",
+            "a",
+            "the quick brown fox",
+        ];
+        for a in words {
+            for b in words {
+                let dist = lev_distance(a, b);
+                for n in 1..4 {
+                    assert!(may_be_within(a, b, n, dist), "{:?} {:?} {}", a, b, n);
+                }
+            }
+        }
+        assert!(!may_be_within("the quick brown fox", "jumps over", 3, 2));
+    }
+}
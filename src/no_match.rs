@@ -0,0 +1,108 @@
+//! Best-match lookup explaining why nothing was suggested.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::{find_best_match_for_name, kernel, Distance};
+
+/// The reason no suggestion was made.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NoMatch {
+    /// There were no candidates to choose from.
+    EmptyCandidates,
+    /// The lookup was empty.
+    EmptyLookup,
+    /// Every candidate was farther away than the maximum distance.
+    AllBeyondThreshold {
+        /// The closest candidate.
+        closest: String,
+        /// The distance to the closest candidate.
+        dist: Distance,
+    },
+}
+
+impl fmt::Display for NoMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoMatch::EmptyCandidates => f.write_str("there are no candidates"),
+            NoMatch::EmptyLookup => f.write_str("the lookup is empty"),
+            NoMatch::AllBeyondThreshold { closest, dist } => write!(
+                f,
+                "no candidate is close enough; the closest is `{}` at distance {}",
+                closest, dist
+            ),
+        }
+    }
+}
+
+impl Error for NoMatch {}
+
+/// Like [`find_best_match_for_name`], but reports why no match was found.
+pub fn try_find_best_match_for_name<T>(
+    iter_names: impl Iterator<Item = T> + Clone,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Result<String, NoMatch>
+where
+    T: AsRef<str>,
+{
+    if lookup.is_empty() {
+        return Err(NoMatch::EmptyLookup);
+    }
+    if let Some(best) = find_best_match_for_name(iter_names.clone(), lookup, dist) {
+        return Ok(best);
+    }
+
+    let (closest, dist) = iter_names
+        .map(|name| {
+            let dist = kernel::distance(lookup, name.as_ref(), None);
+            (name, dist)
+        })
+        .min_by_key(|&(_, dist)| dist)
+        .ok_or(NoMatch::EmptyCandidates)?;
+    Err(NoMatch::AllBeyondThreshold {
+        closest: closest.as_ref().to_string(),
+        dist: Distance(dist),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_find_best_match_for_name() {
+        let input = ["aaab", "aaabc"];
+        assert_eq!(
+            try_find_best_match_for_name(input.iter(), "aaaa", None),
+            Ok("aaab".to_string())
+        );
+        assert_eq!(
+            try_find_best_match_for_name(input.iter(), "aaaxyz", None),
+            Err(NoMatch::AllBeyondThreshold {
+                closest: "aaab".to_string(),
+                dist: Distance(3),
+            })
+        );
+        assert_eq!(
+            try_find_best_match_for_name(input.iter(), "", None),
+            Err(NoMatch::EmptyLookup)
+        );
+        assert_eq!(
+            try_find_best_match_for_name(std::iter::empty::<&str>(), "aaaa", None),
+            Err(NoMatch::EmptyCandidates)
+        );
+    }
+
+    #[test]
+    fn test_no_match_display() {
+        let err = NoMatch::AllBeyondThreshold {
+            closest: "aaab".to_string(),
+            dist: Distance(3),
+        };
+        assert_eq!(
+            err.to_string(),
+            "no candidate is close enough; the closest is `aaab` at distance 3"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Unicode normalization before comparison.
+//!
+//! The same text can be encoded with precomposed or decomposed characters,
+//! e.g. "é" as U+00E9 or as `e` followed by U+0301. Normalizing both sides
+//! first keeps such strings from being edits apart.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::lev_distance;
+
+/// A Unicode normalization form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Canonical composition, which only unifies equivalent encodings.
+    Nfc,
+    /// Compatibility composition, which also unifies variants such as
+    /// ligatures and full-width forms with their plain counterparts.
+    Nfkc,
+}
+
+impl Normalization {
+    /// Normalizes `s` to this form.
+    pub fn normalize(self, s: &str) -> String {
+        match self {
+            Normalization::Nfc => s.nfc().collect(),
+            Normalization::Nfkc => s.nfkc().collect(),
+        }
+    }
+
+    /// Finds the Levenshtein distance between two strings after normalizing
+    /// both to this form.
+    pub fn lev_distance(self, a: &str, b: &str) -> usize {
+        lev_distance(&self.normalize(a), &self.normalize(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(lev_distance("caf\u{e9}", "cafe\u{301}"), 2);
+        assert_eq!(
+            Normalization::Nfc.lev_distance("caf\u{e9}", "cafe\u{301}"),
+            0
+        );
+        assert_eq!(Normalization::Nfc.lev_distance("\u{fb01}le", "file"), 2);
+        assert_eq!(Normalization::Nfkc.lev_distance("\u{fb01}le", "file"), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Matching that scores candidates in parallel with rayon.
+
+use rayon::prelude::*;
+
+use crate::{Pipeline, QueryStats, Rank, Threshold};
+
+/// Like [`find_best_match_for_name`](crate::find_best_match_for_name),
+/// scoring the candidates in parallel.
+///
+/// Takes anything that turns into an indexed parallel iterator, such as a
+/// slice or a `Vec`, so that ties are broken by the original order exactly
+/// as in the sequential version.
+pub fn par_find_best_match_for_name<I>(
+    names: I,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<String>
+where
+    I: IntoParallelIterator,
+    I::Iter: IndexedParallelIterator,
+    I::Item: AsRef<str>,
+{
+    let pipeline = Pipeline {
+        threshold: Threshold::from(dist),
+        ..Pipeline::default()
+    };
+    let max_dist = pipeline.max_dist_for(lookup) as f64;
+    names
+        .into_par_iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| {
+            let rank = pipeline.rank(
+                &pipeline.metric,
+                candidate.as_ref(),
+                i,
+                lookup,
+                max_dist,
+                &mut QueryStats::default(),
+            )?;
+            Some((rank, candidate))
+        })
+        .min_by_key(|&(rank, _)| rank)
+        .map(|(_, candidate)| candidate.as_ref().to_string())
+}
+
+/// Like [`find_best_matches_for_name`](crate::find_best_matches_for_name),
+/// scoring the candidates in parallel.
+pub fn par_find_best_matches_for_name<I>(
+    names: I,
+    lookup: &str,
+    dist: Option<usize>,
+    k: usize,
+) -> Vec<String>
+where
+    I: IntoParallelIterator,
+    I::Iter: IndexedParallelIterator,
+    I::Item: AsRef<str>,
+{
+    let pipeline = Pipeline {
+        threshold: Threshold::from(dist),
+        ..Pipeline::default()
+    };
+    let max_dist = pipeline.max_dist_for(lookup) as f64;
+    let mut ranked: Vec<(Rank, I::Item)> = names
+        .into_par_iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| {
+            let rank = pipeline.rank(
+                &pipeline.metric,
+                candidate.as_ref(),
+                i,
+                lookup,
+                max_dist,
+                &mut QueryStats::default(),
+            )?;
+            Some((rank, candidate))
+        })
+        .collect();
+    ranked.par_sort_unstable_by_key(|&(rank, _)| rank);
+    ranked
+        .into_iter()
+        .take(k)
+        .map(|(_, candidate)| candidate.as_ref().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_best_match_for_name, find_best_matches_for_name};
+
+    const NAMES: &[&str] = &[
+        "println", "print", "eprintln", "PRINT", "b_a_c", "c_b_a", "printl", "format",
+    ];
+
+    #[test]
+    fn test_par_find_best_match_for_name() {
+        for lookup in ["print", "prnt", "a_b_c", "frmat", "nothing_like_it"] {
+            assert_eq!(
+                par_find_best_match_for_name(NAMES, lookup, None),
+                find_best_match_for_name(NAMES.iter(), lookup, None),
+                "{:?}",
+                lookup
+            );
+        }
+        let names: Vec<String> = NAMES.iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            par_find_best_match_for_name(&names, "printn", None),
+            Some("println".to_string())
+        );
+    }
+
+    #[test]
+    fn test_par_find_best_matches_for_name() {
+        for lookup in ["print", "prnt", "a_b_c", "frmat"] {
+            assert_eq!(
+                par_find_best_matches_for_name(NAMES, lookup, Some(2), 4),
+                find_best_matches_for_name(NAMES.iter(), lookup, Some(2), 4),
+                "{:?}",
+                lookup
+            );
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Suggestions for fixed sets of names known at compile time.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::find_best_match_for_name;
+
+/// Types that can be parsed from one of a fixed set of names, suggesting the
+/// closest name when the input doesn't match any of them.
+///
+/// With the `derive` feature enabled, this can be derived for enums of unit
+/// variants.
+pub trait FuzzyFromStr: Sized {
+    /// All names accepted by [`from_str_fuzzy`](FuzzyFromStr::from_str_fuzzy).
+    const VARIANTS: &'static [&'static str];
+
+    /// Parses `s`, or returns an error suggesting the closest accepted name.
+    fn from_str_fuzzy(s: &str) -> Result<Self, FuzzyParseError>;
+}
+
+/// Types with a fixed set of field names that lookups can be checked against.
+///
+/// With the `derive` feature enabled, this can be derived for structs with
+/// named fields.
+pub trait FieldNames {
+    /// The names of all fields, in declaration order.
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Finds the field name closest to `name`.
+    fn suggest_field(name: &str) -> Option<String> {
+        find_best_match_for_name(Self::FIELD_NAMES.iter(), name, None)
+    }
+}
+
+/// The error returned when an input doesn't match any accepted name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyParseError {
+    input: String,
+    suggestion: Option<String>,
+}
+
+impl FuzzyParseError {
+    /// Creates an error for `input`, suggesting the best match among `names`.
+    pub fn new(input: &str, names: &[&str]) -> Self {
+        FuzzyParseError {
+            input: input.to_string(),
+            suggestion: find_best_match_for_name(names.iter(), input, None),
+        }
+    }
+
+    /// The input that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The closest accepted name, if any was close enough.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+}
+
+impl fmt::Display for FuzzyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown variant `{}`", self.input)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FuzzyParseError {}
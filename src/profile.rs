@@ -0,0 +1,153 @@
+//! Preconfigured scoring profiles.
+
+use crate::matcher::QueryContext;
+use crate::sublime_score;
+
+/// How a [`Matcher`](crate::Matcher) ranks candidates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// The pipeline of [`find_best_match_for_name`](crate::find_best_match_for_name).
+    #[default]
+    Default,
+    /// Ranks candidates the way IDE symbol pickers do: the lookup must appear
+    /// in the candidate as a case-insensitive subsequence, and hits at word
+    /// boundaries, on camelCase humps, in runs, and in the file name part of
+    /// a path score higher.
+    ///
+    /// Falls back to [`Profile::Default`] when no candidate contains the
+    /// lookup as a subsequence, so typos are still corrected.
+    IdeSymbols,
+    /// Ranks candidates by [`sublime_score`](crate::sublime_score), falling
+    /// back to [`Profile::Default`] like [`Profile::IdeSymbols`] does.
+    Sublime,
+}
+
+impl Profile {
+    /// Scores `candidate` against `lookup`, or `None` if the profile does
+    /// not score candidates or `candidate` is not a hit.
+    pub(crate) fn score(self, candidate: &str, lookup: &str) -> Option<i64> {
+        match self {
+            Profile::Default => None,
+            Profile::IdeSymbols => ide_score(candidate, lookup),
+            Profile::Sublime => sublime_score(lookup, candidate).map(i64::from),
+        }
+    }
+}
+
+const MATCH: i64 = 1;
+const BOUNDARY: i64 = 8;
+const CAMEL_HUMP: i64 = 7;
+const CONSECUTIVE: i64 = 4;
+const EXACT_CASE: i64 = 1;
+const FILE_NAME: i64 = 2;
+const GAP: i64 = 3;
+
+/// Scores `candidate` against `lookup` under [`Profile::IdeSymbols`], or
+/// `None` if `lookup` is not a subsequence of `candidate`.
+pub(crate) fn ide_score(candidate: &str, lookup: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.chars().collect();
+    let lookup: Vec<char> = lookup.chars().collect();
+    if lookup.len() > candidate.len() {
+        return None;
+    }
+    let file_name_start = candidate
+        .iter()
+        .rposition(|&c| c == '/' || c == '\\')
+        .map_or(0, |i| i + 1);
+    let bonus: Vec<i64> = (0..candidate.len())
+        .map(|j| {
+            let c = candidate[j];
+            let prev = j.checked_sub(1).map(|p| candidate[p]);
+            let mut bonus = MATCH;
+            match prev {
+                None => bonus += BOUNDARY,
+                Some(p) if !p.is_alphanumeric() => bonus += BOUNDARY,
+                Some(p) if p.is_lowercase() && c.is_uppercase() => bonus += CAMEL_HUMP,
+                Some(p) if !p.is_numeric() && c.is_numeric() => bonus += CAMEL_HUMP,
+                _ => {}
+            }
+            if j >= file_name_start {
+                bonus += FILE_NAME;
+            }
+            bonus
+        })
+        .collect();
+
+    // `row[j]` is the best score of the lookup prefix so far with its last
+    // char matched at `candidate[j]`.
+    let mut row: Vec<Option<i64>> = vec![None; candidate.len()];
+    for (i, &lc) in lookup.iter().enumerate() {
+        let mut next = vec![None; candidate.len()];
+        // The best score ending strictly before `j - 1`, for a gapped match.
+        let mut best_before: Option<i64> = None;
+        for j in 0..candidate.len() {
+            let cc = candidate[j];
+            if j >= 2 {
+                best_before = best_before.max(row[j - 2]);
+            }
+            if !cc.to_lowercase().eq(lc.to_lowercase()) {
+                continue;
+            }
+            let here = bonus[j] + if cc == lc { EXACT_CASE } else { 0 };
+            next[j] = if i == 0 {
+                Some(here)
+            } else {
+                let consecutive = j
+                    .checked_sub(1)
+                    .and_then(|p| row[p])
+                    .map(|s| s + CONSECUTIVE);
+                let gapped = best_before.map(|s| s - GAP);
+                consecutive.max(gapped).map(|s| s + here)
+            };
+        }
+        row = next;
+    }
+    row.into_iter().flatten().max()
+}
+
+/// Finds the highest-scoring candidate under `profile`, preferring shorter
+/// candidates and then earlier ones on ties.
+pub(crate) fn find_best_scored_match<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    profile: Profile,
+    ctx: &mut QueryContext,
+) -> Option<T>
+where
+    T: AsRef<str>,
+{
+    let mut best: Option<(i64, usize, T)> = None;
+    for candidate in iter_names {
+        if ctx.should_stop() {
+            break;
+        }
+        ctx.stats.candidates_scanned += 1;
+        let Some(score) = profile.score(candidate.as_ref(), lookup) else {
+            continue;
+        };
+        let len = candidate.as_ref().chars().count();
+        if best
+            .as_ref()
+            .is_none_or(|&(s, l, _)| score > s || (score == s && len < l))
+        {
+            best = Some((score, len, candidate));
+        }
+    }
+    best.map(|(_, _, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ide_score() {
+        assert_eq!(ide_score("println", "xyz"), None);
+        assert_eq!(ide_score("ab", "abc"), None);
+        // Humps and word boundaries beat scattered hits.
+        assert!(ide_score("getFooBar", "gfb") > ide_score("gaffeb", "gfb"));
+        assert!(ide_score("find_best_match", "fbm") > ide_score("fabmatch", "fbm"));
+        // Hits in the file name beat hits in the directories.
+        assert!(ide_score("src/main.rs", "main") > ide_score("main/src.rs", "main"));
+    }
+}
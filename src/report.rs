@@ -0,0 +1,83 @@
+//! Several signals about a pair of strings at once.
+
+use std::cmp;
+
+use crate::static_match::{eq_ignore_case, same_words};
+use crate::{kernel, Distance, Threshold};
+
+/// The tier of [`find_best_match_for_name`](crate::find_best_match_for_name)
+/// that would accept a candidate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /// The candidate equals the lookup ignoring case.
+    CaseInsensitive,
+    /// The candidate is within the default edit distance of the lookup.
+    Levenshtein,
+    /// The candidate has the same `_`-separated words as the lookup.
+    SortedWords,
+}
+
+/// Distance, similarity, shared prefix, and tier of a candidate against a
+/// lookup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreReport {
+    /// The Levenshtein distance.
+    pub distance: Distance,
+    /// The similarity in `0.0..=1.0`, where `1.0` means equal.
+    pub similarity: f64,
+    /// The number of leading chars the strings share.
+    pub prefix_len: usize,
+    /// The tier that would accept the candidate, if any.
+    pub tier: Option<Tier>,
+}
+
+impl ScoreReport {
+    /// Scores `candidate` against `lookup`, computing the distance once.
+    pub fn new(lookup: &str, candidate: &str) -> ScoreReport {
+        let distance = Distance(kernel::distance(lookup, candidate, None));
+        let prefix_len = lookup
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let len = cmp::max(lookup.chars().count(), candidate.chars().count());
+        let max_dist = Threshold::OneThird.max_dist(lookup);
+        let tier = if eq_ignore_case(candidate, lookup) {
+            Some(Tier::CaseInsensitive)
+        } else if distance.within(max_dist) {
+            Some(Tier::Levenshtein)
+        } else if same_words(candidate, lookup) {
+            Some(Tier::SortedWords)
+        } else {
+            None
+        };
+        ScoreReport {
+            distance,
+            similarity: 1.0 - distance.normalized(len),
+            prefix_len,
+            tier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_report() {
+        let report = ScoreReport::new("printn", "println");
+        assert_eq!(report.distance, Distance(1));
+        assert_eq!(report.similarity, 1.0 - 1.0 / 7.0);
+        assert_eq!(report.prefix_len, 5);
+        assert_eq!(report.tier, Some(Tier::Levenshtein));
+
+        assert_eq!(
+            ScoreReport::new("FOO", "foo").tier,
+            Some(Tier::CaseInsensitive)
+        );
+        assert_eq!(ScoreReport::new("b_a", "a_b").tier, Some(Tier::SortedWords));
+        assert_eq!(ScoreReport::new("abc", "xyz").tier, None);
+        assert_eq!(ScoreReport::new("", "").similarity, 1.0);
+    }
+}
@@ -0,0 +1,154 @@
+//! Suggestions for schema identifiers such as SQL columns or CSV headers.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::find_best_match_for_name;
+
+/// Checks names against a fixed set of known identifiers.
+///
+/// By default identifiers are compared case-insensitively and with spaces
+/// and underscores treated as equivalent, so `User ID` is accepted for a
+/// `user_id` column.
+#[derive(Clone, Debug)]
+pub struct SchemaSuggester {
+    names: Vec<String>,
+    normalized: Vec<String>,
+    case_sensitive: bool,
+    separator_sensitive: bool,
+}
+
+impl SchemaSuggester {
+    /// Creates a suggester for the given known identifiers.
+    pub fn new<T>(names: impl IntoIterator<Item = T>) -> Self
+    where
+        T: Into<String>,
+    {
+        let mut suggester = SchemaSuggester {
+            names: names.into_iter().map(Into::into).collect(),
+            normalized: Vec::new(),
+            case_sensitive: false,
+            separator_sensitive: false,
+        };
+        suggester.renormalize();
+        suggester
+    }
+
+    /// Sets whether identifiers are compared case-sensitively.
+    pub fn case_sensitive(mut self, yes: bool) -> Self {
+        self.case_sensitive = yes;
+        self.renormalize();
+        self
+    }
+
+    /// Sets whether spaces and underscores are distinguished.
+    pub fn separator_sensitive(mut self, yes: bool) -> Self {
+        self.separator_sensitive = yes;
+        self.renormalize();
+        self
+    }
+
+    fn renormalize(&mut self) {
+        self.normalized = self.names.iter().map(|n| self.normalize(n)).collect();
+    }
+
+    fn normalize(&self, name: &str) -> String {
+        let name = if self.case_sensitive {
+            name.to_string()
+        } else {
+            name.to_lowercase()
+        };
+        if self.separator_sensitive {
+            name
+        } else {
+            name.replace(' ', "_")
+        }
+    }
+
+    /// Checks `name` against the known identifiers.
+    ///
+    /// Returns the identifier as it was registered, or an error carrying the
+    /// closest one if `name` is unknown.
+    pub fn check(&self, name: &str) -> Result<&str, UnknownIdentifier> {
+        let lookup = self.normalize(name);
+        if let Some(i) = self.normalized.iter().position(|n| *n == lookup) {
+            return Ok(&self.names[i]);
+        }
+
+        let suggestion = find_best_match_for_name(self.normalized.iter(), &lookup, None)
+            .and_then(|best| self.normalized.iter().position(|n| *n == best))
+            .map(|i| self.names[i].clone());
+        Err(UnknownIdentifier {
+            name: name.to_string(),
+            suggestion,
+        })
+    }
+}
+
+/// The error returned by [`SchemaSuggester::check`] for unknown identifiers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownIdentifier {
+    name: String,
+    suggestion: Option<String>,
+}
+
+impl UnknownIdentifier {
+    /// The identifier that was checked.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The closest known identifier, if any was close enough.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+}
+
+impl fmt::Display for UnknownIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown identifier `{}`", self.name)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnknownIdentifier {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let schema = SchemaSuggester::new(["user_id", "created_at", "Email"]);
+        assert_eq!(schema.check("user_id"), Ok("user_id"));
+        assert_eq!(schema.check("User ID"), Ok("user_id"));
+        assert_eq!(schema.check("email"), Ok("Email"));
+
+        let err = schema.check("created at_").unwrap_err();
+        assert_eq!(err.suggestion(), Some("created_at"));
+        assert_eq!(
+            err.to_string(),
+            "unknown identifier `created at_`, did you mean `created_at`?"
+        );
+        assert_eq!(schema.check("price").unwrap_err().suggestion(), None);
+    }
+
+    #[test]
+    fn test_check_sensitive() {
+        let schema = SchemaSuggester::new(["user_id", "Email"])
+            .case_sensitive(true)
+            .separator_sensitive(true);
+        assert_eq!(schema.check("Email"), Ok("Email"));
+        assert_eq!(
+            schema.check("email").unwrap_err().suggestion(),
+            Some("Email")
+        );
+        assert_eq!(
+            schema.check("user id").unwrap_err().suggestion(),
+            Some("user_id")
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! "Did you mean" hints for serde errors.
+//!
+//! serde reports unknown fields and variants as
+//! ``unknown field `x`, expected one of `a`, `b`, `c` ``, which already
+//! contains everything needed to make a suggestion. This works on the
+//! rendered message, so it applies to any serde data format without a
+//! dependency on serde itself.
+
+use std::fmt;
+
+use crate::find_best_match_for_name;
+
+const UNKNOWN_PREFIXES: &[&str] = &["unknown field `", "unknown variant `"];
+
+/// Rewrites a serde error message to include a suggestion for the closest
+/// expected field or variant.
+///
+/// Messages that don't report an unknown field or variant, or for which no
+/// expected name is close enough, are returned unchanged.
+pub fn enrich_serde_error<E>(err: &E) -> String
+where
+    E: fmt::Display + ?Sized,
+{
+    let message = err.to_string();
+    match suggest_from_serde_message(&message) {
+        Some((insert_at, suggestion)) => format!(
+            "{} (did you mean `{}`?){}",
+            &message[..insert_at],
+            suggestion,
+            &message[insert_at..]
+        ),
+        None => message,
+    }
+}
+
+/// Returns the byte offset just past the expected-names list and the best
+/// suggestion from it.
+fn suggest_from_serde_message(message: &str) -> Option<(usize, String)> {
+    let (start, prefix) = UNKNOWN_PREFIXES
+        .iter()
+        .filter_map(|prefix| message.find(prefix).map(|start| (start, prefix)))
+        .min_by_key(|&(start, _)| start)?;
+    let input_start = start + prefix.len();
+    let input_len = message[input_start..].find('`')?;
+    let input = &message[input_start..input_start + input_len];
+
+    let mut pos = input_start + input_len + 1;
+    let rest = &message[pos..];
+    let list = rest
+        .strip_prefix(", expected one of ")
+        .or_else(|| rest.strip_prefix(", expected "))?;
+    pos += rest.len() - list.len();
+
+    let mut expected = Vec::new();
+    loop {
+        let name = message[pos..].strip_prefix('`')?;
+        let len = name.find('`')?;
+        expected.push(&name[..len]);
+        pos += len + 2;
+
+        let rest = &message[pos..];
+        match [", ", " or "].iter().find(|sep| rest.starts_with(**sep)) {
+            Some(sep) if rest[sep.len()..].starts_with('`') => pos += sep.len(),
+            _ => break,
+        }
+    }
+
+    let suggestion = find_best_match_for_name(expected.iter(), input, None)?;
+    Some((pos, suggestion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrich_serde_error() {
+        assert_eq!(
+            enrich_serde_error("unknown field `nme`, expected one of `name`, `age`"),
+            "unknown field `nme`, expected one of `name`, `age` (did you mean `name`?)"
+        );
+        assert_eq!(
+            enrich_serde_error("unknown variant `Verbos`, expected `Quiet` or `Verbose` at line 1 column 9"),
+            "unknown variant `Verbos`, expected `Quiet` or `Verbose` (did you mean `Verbose`?) at line 1 column 9"
+        );
+    }
+
+    #[test]
+    fn test_enrich_serde_error_unchanged() {
+        for message in [
+            "unknown field `zzzzzz`, expected one of `name`, `age`",
+            "unknown field `name`, there are no fields",
+            "invalid type: integer `1`, expected a string",
+        ] {
+            assert_eq!(enrich_serde_error(message), message);
+        }
+    }
+}
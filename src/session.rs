@@ -0,0 +1,168 @@
+//! Incremental matching for typeahead.
+//!
+//! While the user types, each lookup differs from the previous one by a
+//! character or two at the end. A [`QuerySession`] keeps the DP rows of every
+//! candidate for each prefix of the lookup, along with the top candidates of
+//! each lookup it has answered. A keystroke only computes one new row per
+//! candidate and selects the new top candidates in linear time, and deleting
+//! characters back to an earlier lookup computes nothing at all.
+
+use std::cmp;
+
+use crate::Distance;
+
+/// A typeahead session over a fixed candidate set.
+#[derive(Clone, Debug)]
+pub struct QuerySession {
+    candidates: Vec<Vec<char>>,
+    names: Vec<String>,
+    lookup: Vec<char>,
+    /// For each candidate, the DP rows for every prefix of the lookup, laid
+    /// out back to back with a stride of the candidate's length plus one.
+    rows: Vec<Vec<usize>>,
+    k: usize,
+    /// For every prefix of the lookup, its top candidates if they were
+    /// selected, as indices with distances.
+    tops: Vec<Option<Vec<(usize, usize)>>>,
+}
+
+impl QuerySession {
+    /// Creates a session reporting the `k` closest candidates.
+    pub fn new<T>(candidates: impl IntoIterator<Item = T>, k: usize) -> Self
+    where
+        T: Into<String>,
+    {
+        let names: Vec<String> = candidates.into_iter().map(Into::into).collect();
+        let candidates: Vec<Vec<char>> = names.iter().map(|name| name.chars().collect()).collect();
+        let rows = candidates
+            .iter()
+            .map(|candidate| (0..=candidate.len()).collect())
+            .collect();
+        let mut session = QuerySession {
+            candidates,
+            names,
+            lookup: Vec::new(),
+            rows,
+            k,
+            tops: vec![None],
+        };
+        session.refresh_top();
+        session
+    }
+
+    /// The current lookup.
+    pub fn lookup(&self) -> String {
+        self.lookup.iter().collect()
+    }
+
+    /// Replaces the lookup, reusing the work done for the prefix it shares
+    /// with the previous one, and returns the new top candidates.
+    pub fn update(&mut self, lookup: &str) -> Vec<(&str, Distance)> {
+        let lookup: Vec<char> = lookup.chars().collect();
+        let shared = self
+            .lookup
+            .iter()
+            .zip(&lookup)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        for (candidate, rows) in self.candidates.iter().zip(&mut self.rows) {
+            let stride = candidate.len() + 1;
+            rows.truncate((shared + 1) * stride);
+            for (i, &lc) in lookup.iter().enumerate().skip(shared) {
+                let prev = i * stride;
+                rows.push(i + 1);
+                for (j, &cc) in candidate.iter().enumerate() {
+                    let diagonal = rows[prev + j] + usize::from(lc != cc);
+                    let above = rows[prev + j + 1] + 1;
+                    let left = rows[prev + stride + j] + 1;
+                    rows.push(cmp::min(cmp::min(diagonal, above), left));
+                }
+            }
+        }
+        self.tops.truncate(shared + 1);
+        self.tops.resize(lookup.len() + 1, None);
+        self.lookup = lookup;
+        self.refresh_top();
+        self.top()
+    }
+
+    /// The `k` closest candidates to the current lookup, closest first.
+    pub fn top(&self) -> Vec<(&str, Distance)> {
+        self.tops[self.lookup.len()]
+            .iter()
+            .flatten()
+            .map(|&(i, dist)| (self.names[i].as_str(), Distance(dist)))
+            .collect()
+    }
+
+    /// Selects the top candidates of the current lookup, unless they are
+    /// known from before.
+    fn refresh_top(&mut self) {
+        let top = &mut self.tops[self.lookup.len()];
+        if top.is_some() {
+            return;
+        }
+        let mut scored: Vec<(usize, usize)> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, rows)| (i, rows[rows.len() - 1]))
+            .collect();
+        let key = |&(i, dist): &(usize, usize)| (dist, i);
+        if self.k < scored.len() {
+            if self.k > 0 {
+                scored.select_nth_unstable_by_key(self.k - 1, key);
+            }
+            scored.truncate(self.k);
+        }
+        scored.sort_unstable_by_key(key);
+        *top = Some(scored);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_update() {
+        let mut session = QuerySession::new(["print", "println", "format", "write"], 2);
+        assert_eq!(
+            session.update("pr"),
+            vec![("print", Distance(3)), ("write", Distance(4))]
+        );
+        assert_eq!(
+            session.update("prin"),
+            vec![("print", Distance(1)), ("println", Distance(3))]
+        );
+        assert_eq!(session.update("prit")[0], ("print", Distance(1)));
+        assert_eq!(session.lookup(), "prit");
+        // "pr" was answered before, so its top candidates are kept.
+        assert!(session.tops[2].is_some() && session.tops[3].is_none());
+        assert_eq!(
+            session.update("pr"),
+            vec![("print", Distance(3)), ("write", Distance(4))]
+        );
+    }
+
+    #[test]
+    fn test_matches_lev_distance() {
+        let candidates = ["größe", "grosse", "gruß", ""];
+        let mut session = QuerySession::new(candidates, 4);
+        for lookup in ["g", "gr", "grö", "größ", "gr", "gru", "", "gruss"] {
+            let mut expected: Vec<_> = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (lev_distance(lookup, c), i))
+                .collect();
+            expected.sort();
+            let expected: Vec<_> = expected
+                .into_iter()
+                .map(|(d, i)| (candidates[i], Distance(d)))
+                .collect();
+            assert_eq!(session.update(lookup), expected, "{:?}", lookup);
+        }
+    }
+}
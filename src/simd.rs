@@ -0,0 +1,161 @@
+//! Batched distances with SIMD, selected at runtime.
+//!
+//! Myers' algorithm keeps a whole DP column in one 64-bit word, so several
+//! candidates can be compared against the same lookup at once, one per SIMD
+//! lane.
+
+use crate::kernel::{self, PatternMasks};
+
+/// Finds the Levenshtein distances from `lookup` to each of `candidates`.
+///
+/// Lookups of up to 64 chars are compared against four candidates at a time
+/// when the CPU supports AVX2, and against one at a time otherwise. Longer
+/// lookups always take the regular path.
+pub fn lev_distance_many<T: AsRef<str>>(lookup: &str, candidates: &[T]) -> Vec<usize> {
+    let pattern: Vec<char> = lookup.chars().collect();
+    if pattern.is_empty() || pattern.len() > kernel::MYERS_MAX_LEN {
+        return candidates
+            .iter()
+            .map(|candidate| kernel::distance(lookup, candidate.as_ref(), None))
+            .collect();
+    }
+    let masks = PatternMasks::new(&pattern);
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: AVX2 is supported, as just checked.
+        return unsafe { avx2::distances(&masks, pattern.len(), candidates) };
+    }
+    scalar_distances(&masks, pattern.len(), candidates)
+}
+
+/// The fallback of [`lev_distance_many`] without SIMD.
+fn scalar_distances<T: AsRef<str>>(masks: &PatternMasks, m: usize, candidates: &[T]) -> Vec<usize> {
+    candidates
+        .iter()
+        .map(|candidate| kernel::myers(m, candidate.as_ref().chars().map(|c| masks.get(c))))
+        .collect()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use crate::kernel::PatternMasks;
+
+    const LANES: usize = 4;
+
+    /// Runs Myers' algorithm over `candidates` in batches of [`LANES`].
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support AVX2, and `m` must be in `1..=64`.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn distances<T: AsRef<str>>(
+        masks: &PatternMasks,
+        m: usize,
+        candidates: &[T],
+    ) -> Vec<usize> {
+        let mut out = Vec::with_capacity(candidates.len());
+        for chunk in candidates.chunks(LANES) {
+            let texts: Vec<Vec<u64>> = chunk
+                .iter()
+                .map(|candidate| candidate.as_ref().chars().map(|c| masks.get(c)).collect())
+                .collect();
+            out.extend_from_slice(&batch(m, &texts)[..texts.len()]);
+        }
+        out
+    }
+
+    /// Runs Myers' algorithm over up to [`LANES`] texts given as the masks
+    /// of their chars.
+    #[target_feature(enable = "avx2")]
+    unsafe fn batch(m: usize, texts: &[Vec<u64>]) -> [usize; LANES] {
+        let mut result = [m; LANES];
+        let max_len = texts.iter().map(Vec::len).max().unwrap_or(0);
+        let ones = _mm256_set1_epi64x(-1);
+        let one = _mm256_set1_epi64x(1);
+        let last = _mm_cvtsi64_si128((m - 1) as i64);
+        let mut pv = ones;
+        let mut mv = _mm256_setzero_si256();
+        let mut score = _mm256_set1_epi64x(m as i64);
+        for t in 0..max_len {
+            let lane = |i: usize| {
+                texts
+                    .get(i)
+                    .and_then(|text| text.get(t))
+                    .map_or(0, |&eq| eq as i64)
+            };
+            let eq = _mm256_set_epi64x(lane(3), lane(2), lane(1), lane(0));
+
+            let xv = _mm256_or_si256(eq, mv);
+            let sum = _mm256_add_epi64(_mm256_and_si256(eq, pv), pv);
+            let xh = _mm256_or_si256(_mm256_xor_si256(sum, pv), eq);
+            let ph = _mm256_or_si256(mv, _mm256_andnot_si256(_mm256_or_si256(xh, pv), ones));
+            let mh = _mm256_and_si256(pv, xh);
+            let ph_last = _mm256_and_si256(_mm256_srl_epi64(ph, last), one);
+            let mh_last = _mm256_and_si256(_mm256_srl_epi64(mh, last), one);
+            score = _mm256_sub_epi64(_mm256_add_epi64(score, ph_last), mh_last);
+            let ph = _mm256_or_si256(_mm256_slli_epi64(ph, 1), one);
+            let mh = _mm256_slli_epi64(mh, 1);
+            pv = _mm256_or_si256(mh, _mm256_andnot_si256(_mm256_or_si256(xv, ph), ones));
+            mv = _mm256_and_si256(ph, xv);
+
+            if texts.iter().any(|text| text.len() == t + 1) {
+                let mut scores = [0i64; LANES];
+                _mm256_storeu_si256(scores.as_mut_ptr().cast(), score);
+                for (i, text) in texts.iter().enumerate() {
+                    if text.len() == t + 1 {
+                        result[i] = scores[i] as usize;
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    const CANDIDATES: &[&str] = &[
+        "",
+        "a",
+        "kitten",
+        "sitting",
+        "größe",
+        "grosse",
+        "println",
+        "print",
+        "a_much_longer_name",
+    ];
+
+    #[test]
+    fn test_lev_distance_many() {
+        let long = "x".repeat(70);
+        for lookup in ["", "kitten", "grösse", "prnt", long.as_str()] {
+            let expected: Vec<usize> = CANDIDATES.iter().map(|c| lev_distance(lookup, c)).collect();
+            assert_eq!(
+                lev_distance_many(lookup, CANDIDATES),
+                expected,
+                "{:?}",
+                lookup
+            );
+        }
+    }
+
+    #[test]
+    fn test_scalar_distances() {
+        let pattern: Vec<char> = "kitten".chars().collect();
+        let masks = PatternMasks::new(&pattern);
+        let expected: Vec<usize> = CANDIDATES
+            .iter()
+            .map(|c| lev_distance("kitten", c))
+            .collect();
+        assert_eq!(
+            scalar_distances(&masks, pattern.len(), CANDIDATES),
+            expected
+        );
+    }
+}
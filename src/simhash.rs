@@ -0,0 +1,78 @@
+//! SimHash fingerprints for near-duplicate detection.
+//!
+//! A SimHash is a 64-bit fingerprint in which similar strings differ in few
+//! bits. Comparing fingerprints is a constant-time pre-check; only strings
+//! whose fingerprints collide need their exact edit distance computed.
+
+use crate::hash::ngram_hashes;
+use crate::kernel;
+
+/// The n-gram size used by [`simhash`].
+const DEFAULT_NGRAM: usize = 3;
+
+/// Computes the SimHash of `s` over character trigrams.
+pub fn simhash(s: &str) -> u64 {
+    simhash_with_ngram(s, DEFAULT_NGRAM)
+}
+
+/// Computes the SimHash of `s` over character n-grams of size `n`.
+pub fn simhash_with_ngram(s: &str, n: usize) -> u64 {
+    let mut weights = [0i64; 64];
+    for hash in ngram_hashes(s, n.max(1)) {
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, &weight)| weight > 0)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | 1 << bit)
+}
+
+/// The number of bits in which two fingerprints differ.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Checks whether `a` and `b` are near duplicates.
+///
+/// Strings whose fingerprints differ in more than `max_hamming` bits are
+/// rejected outright; otherwise the edit distance must be at most
+/// `max_dist`.
+pub fn is_near_duplicate(a: &str, b: &str, max_hamming: u32, max_dist: usize) -> bool {
+    hamming_distance(simhash(a), simhash(b)) <= max_hamming
+        && kernel::distance(a, b, Some(max_dist)) <= max_dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "connection to database server timed out after 30 seconds";
+
+    #[test]
+    fn test_simhash() {
+        assert_eq!(simhash(LINE), simhash(LINE));
+        let similar = "connection to database server timed out after 31 seconds";
+        let different = "user logged in successfully from a new device today";
+        assert!(
+            hamming_distance(simhash(LINE), simhash(similar))
+                < hamming_distance(simhash(LINE), simhash(different))
+        );
+    }
+
+    #[test]
+    fn test_is_near_duplicate() {
+        let similar = "connection to database server timed out after 31 seconds";
+        assert!(is_near_duplicate(LINE, similar, 16, 2));
+        assert!(!is_near_duplicate(LINE, similar, 16, 0));
+        assert!(!is_near_duplicate(LINE, "something else entirely", 16, 100));
+        // "abc" is a single trigram, which "abcd" shares.
+        assert!(is_near_duplicate("abc", "abcd", 16, 1));
+    }
+}
@@ -0,0 +1,110 @@
+//! Fuzzy search over sorted dictionaries without an index.
+//!
+//! A DP row over the query is the state of a [`LevenshteinAutomaton`] after
+//! reading a prefix of a word. In a sorted slice, consecutive words share
+//! prefixes, so the rows for the shared prefix are reused instead of
+//! recomputed. And once every entry of a row exceeds the limit, no word with
+//! that prefix can match, so the whole run of words sharing it is skipped.
+
+use crate::LevenshteinAutomaton;
+
+/// Finds the words within `max_dist` edits of `query`.
+///
+/// `words` must be sorted in ascending order, as by `slice::sort`; for
+/// unsorted input some matches may be missed. Returns `(index, distance)`
+/// pairs in ascending index order.
+pub fn search_sorted<T>(words: &[T], query: &str, max_dist: usize) -> Vec<(usize, usize)>
+where
+    T: AsRef<str>,
+{
+    let automaton = LevenshteinAutomaton::new(query, max_dist);
+    let width = query.chars().count() + 1;
+    let mut rows: Vec<Vec<usize>> = vec![automaton.start().row().to_vec()];
+    let mut path: Vec<(char, usize)> = Vec::new();
+    let mut matches = Vec::new();
+
+    let mut i = 0;
+    'words: while i < words.len() {
+        let word = words[i].as_ref();
+        // Reuse the rows for the prefix shared with the previous word.
+        let mut depth = path
+            .iter()
+            .zip(word.chars())
+            .take_while(|((p, _), c)| p == c)
+            .count();
+        path.truncate(depth);
+        rows.truncate(depth + 1);
+
+        let mut offset = path.last().map_or(0, |&(_, end)| end);
+        for c in word[offset..].chars() {
+            let mut next = Vec::with_capacity(width);
+            automaton.step_into(&rows[depth], c, &mut next);
+            offset += c.len_utf8();
+            depth += 1;
+            path.push((c, offset));
+            let dead = next.iter().all(|&d| d > max_dist);
+            rows.push(next);
+            if dead {
+                // No word starting with this prefix can match.
+                let prefix = &word[..offset];
+                i += words[i..].partition_point(|w| w.as_ref().starts_with(prefix));
+                continue 'words;
+            }
+        }
+
+        let dist = rows[depth][width - 1];
+        if dist <= max_dist {
+            matches.push((i, dist));
+        }
+        i += 1;
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_search_sorted() {
+        let mut words = vec![
+            "apple",
+            "applet",
+            "application",
+            "apply",
+            "banana",
+            "band",
+            "bandana",
+            "can",
+            "candy",
+            "cane",
+        ];
+        words.sort();
+        assert_eq!(
+            search_sorted(&words, "aple", 2),
+            vec![(0, 1), (1, 2), (3, 2)]
+        );
+        assert_eq!(search_sorted(&words, "band", 0), vec![(5, 0)]);
+        assert_eq!(search_sorted(&words, "zzz", 1), vec![]);
+    }
+
+    #[test]
+    fn test_search_sorted_matches_linear_scan() {
+        let mut words = vec![
+            "über", "uber", "überall", "ubel", "abc", "abd", "ab", "a", "", "xyzzy",
+        ];
+        words.sort();
+        for query in ["uber", "ab", "", "überal", "xyz"] {
+            for max_dist in 0..3 {
+                let expected: Vec<_> = words
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| (i, lev_distance(query, w)))
+                    .filter(|&(_, d)| d <= max_dist)
+                    .collect();
+                assert_eq!(search_sorted(&words, query, max_dist), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Allocation-free matching over static candidate tables.
+//!
+//! Some CLIs forbid heap use on their error paths. [`find_best_static_match`]
+//! runs the same three tiers as
+//! [`find_best_match_for_name`](crate::find_best_match_for_name) using only
+//! stack memory and returns the winner by reference.
+
+use std::cmp;
+
+use crate::{CaseFolding, Threshold};
+
+/// The longest lookup, in characters, that fits the stack-allocated DP row.
+pub const MAX_STATIC_LOOKUP_LEN: usize = 255;
+
+/// Compares two strings case-insensitively without allocating.
+pub(crate) fn eq_ignore_case(a: &str, b: &str) -> bool {
+    CaseFolding::Unicode.eq(a, b)
+}
+
+/// The Levenshtein distance with the DP row over `lookup` on the stack.
+fn stack_distance(lookup: &str, candidate: &str) -> usize {
+    let mut row = [0; MAX_STATIC_LOOKUP_LEN + 1];
+    let mut len = 0;
+    for (j, slot) in row.iter_mut().enumerate().take(lookup.chars().count() + 1) {
+        *slot = j;
+        len = j;
+    }
+
+    for (i, c) in candidate.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, l) in lookup.chars().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if c == l {
+                diagonal
+            } else {
+                cmp::min(cmp::min(diagonal, above), row[j]) + 1
+            };
+            diagonal = above;
+        }
+    }
+    row[len]
+}
+
+/// Whether `a` and `b` consist of the same `_`-separated words in any order.
+pub(crate) fn same_words(a: &str, b: &str) -> bool {
+    let count = |s: &str, word: &str| s.split('_').filter(|w| *w == word).count();
+    a.split('_').count() == b.split('_').count()
+        && a.split('_').all(|word| count(a, word) == count(b, word))
+}
+
+/// Finds the best match for `lookup` in `candidates` without allocating.
+///
+/// Behaves like [`find_best_match_for_name`](crate::find_best_match_for_name)
+/// but returns the matching entry of `candidates` itself. Lookups longer than
+/// [`MAX_STATIC_LOOKUP_LEN`] characters never match.
+pub fn find_best_static_match<'a>(
+    candidates: &[&'a str],
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<&'a str> {
+    if lookup.chars().count() > MAX_STATIC_LOOKUP_LEN {
+        return None;
+    }
+    let max_dist = Threshold::from(dist).max_dist(lookup);
+
+    let exact = candidates.iter().find(|c| **c == lookup);
+    if let Some(candidate) = exact.or_else(|| candidates.iter().find(|c| eq_ignore_case(c, lookup)))
+    {
+        return Some(candidate);
+    }
+
+    let mut best: Option<(&'a str, usize)> = None;
+    for &candidate in candidates {
+        let dist = stack_distance(lookup, candidate);
+        if dist <= max_dist && best.is_none_or(|(_, d)| dist < d) {
+            best = Some((candidate, dist));
+        }
+    }
+    if let Some((candidate, _)) = best {
+        return Some(candidate);
+    }
+
+    candidates
+        .iter()
+        .rev()
+        .find(|c| same_words(c, lookup))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_best_match_for_name;
+
+    static CANDIDATES: &[&str] = &["aaab", "aaabc", "AAAA", "a_longer_variable_name", "größe"];
+
+    #[test]
+    fn test_find_best_static_match() {
+        assert_eq!(
+            find_best_static_match(&CANDIDATES[..2], "aaaa", None),
+            Some("aaab")
+        );
+        assert_eq!(
+            find_best_static_match(CANDIDATES, "aaaa", None),
+            Some("AAAA")
+        );
+        assert_eq!(find_best_static_match(CANDIDATES, "1111111111", None), None);
+        assert_eq!(
+            find_best_static_match(CANDIDATES, "a_variable_longer_name", None),
+            Some("a_longer_variable_name")
+        );
+        assert_eq!(
+            find_best_static_match(CANDIDATES, "GRÖSSE", None),
+            Some("größe")
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_find_best_match_for_name() {
+        for lookup in [
+            "aaaa",
+            "aab",
+            "aaabcd",
+            "name_variable_longer_a",
+            "gröse",
+            "",
+        ] {
+            assert_eq!(
+                find_best_static_match(CANDIDATES, lookup, None).map(str::to_string),
+                find_best_match_for_name(CANDIDATES.iter(), lookup, None),
+                "{:?}",
+                lookup
+            );
+        }
+    }
+}
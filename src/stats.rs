@@ -0,0 +1,174 @@
+//! Statistics over the distances from a lookup to a candidate set.
+
+use crate::{lev_distance, IteratorExt};
+
+/// The distribution of distances from a lookup to a set of candidates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistanceStats {
+    sorted: Vec<usize>,
+}
+
+impl DistanceStats {
+    /// The number of candidates.
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// The smallest distance, or `None` if there were no candidates.
+    pub fn min(&self) -> Option<usize> {
+        self.sorted.first().copied()
+    }
+
+    /// The largest distance, or `None` if there were no candidates.
+    pub fn max(&self) -> Option<usize> {
+        self.sorted.last().copied()
+    }
+
+    /// The mean distance, or `None` if there were no candidates.
+    pub fn mean(&self) -> Option<f64> {
+        if self.sorted.is_empty() {
+            return None;
+        }
+        Some(self.sorted.iter().sum::<usize>() as f64 / self.sorted.len() as f64)
+    }
+
+    /// The distance below which `p` percent of the distances fall, using the
+    /// nearest-rank method. `p` is clamped to `0.0..=100.0`.
+    pub fn percentile(&self, p: f64) -> Option<usize> {
+        if self.sorted.is_empty() {
+            return None;
+        }
+        let p = p.clamp(0.0, 100.0);
+        let rank = (p / 100.0 * self.sorted.len() as f64).ceil() as usize;
+        Some(self.sorted[rank.saturating_sub(1)])
+    }
+
+    /// How far the closest candidate stands out from the rest: the mean
+    /// distance minus the minimum.
+    ///
+    /// A separation near zero means the lookup is about equally far from
+    /// everything, in which case no suggestion should be shown.
+    pub fn separation(&self) -> Option<f64> {
+        Some(self.mean()? - self.min()? as f64)
+    }
+}
+
+/// Computes the distribution of distances from `lookup` to every candidate.
+pub fn distance_stats<T>(lookup: &str, iter_names: impl Iterator<Item = T>) -> DistanceStats
+where
+    T: AsRef<str>,
+{
+    let mut sorted: Vec<usize> = iter_names
+        .map(|name| lev_distance(lookup, name.as_ref()))
+        .collect();
+    sorted.sort_unstable();
+    DistanceStats { sorted }
+}
+
+/// The distribution of best-match distances over a sample of lookups.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DistanceHistogram {
+    counts: Vec<usize>,
+    /// The best-match distance and char length of every lookup.
+    samples: Vec<(usize, usize)>,
+}
+
+impl DistanceHistogram {
+    /// The number of lookups with a best match.
+    pub fn samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// The number of lookups by the distance to their best match.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The number of lookups whose best match is within `max_dist` edits.
+    pub fn within(&self, max_dist: usize) -> usize {
+        self.counts.iter().take(max_dist.saturating_add(1)).sum()
+    }
+
+    /// The number of lookups whose best match is within `ratio` times their
+    /// length in chars.
+    pub fn within_ratio(&self, ratio: f64) -> usize {
+        self.samples
+            .iter()
+            .filter(|&&(dist, len)| dist as f64 <= ratio * len as f64)
+            .count()
+    }
+}
+
+/// Buckets the distance from every sample lookup to its best match among
+/// `candidates`, for choosing a threshold from real lookups.
+///
+/// Lookups are skipped if there are no candidates.
+pub fn distance_histogram<T, U>(
+    lookup_samples: impl IntoIterator<Item = T>,
+    candidates: impl Iterator<Item = U> + Clone,
+) -> DistanceHistogram
+where
+    T: AsRef<str>,
+    U: AsRef<str>,
+{
+    let mut counts = Vec::new();
+    let mut samples = Vec::new();
+    for lookup in lookup_samples {
+        let lookup = lookup.as_ref();
+        let Some((_, dist)) = candidates.clone().min_by_lev_distance(lookup) else {
+            continue;
+        };
+        let dist = dist.get();
+        if counts.len() <= dist {
+            counts.resize(dist + 1, 0);
+        }
+        counts[dist] += 1;
+        samples.push((dist, lookup.chars().count()));
+    }
+    DistanceHistogram { counts, samples }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_stats() {
+        let stats = distance_stats("abc", ["abc", "abd", "xyz", "abcdef"].iter());
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.min(), Some(0));
+        assert_eq!(stats.max(), Some(3));
+        assert_eq!(stats.mean(), Some(1.75));
+        assert_eq!(stats.percentile(50.0), Some(1));
+        assert_eq!(stats.percentile(100.0), Some(3));
+        assert_eq!(stats.percentile(0.0), Some(0));
+        assert_eq!(stats.separation(), Some(1.75));
+    }
+
+    #[test]
+    fn test_distance_stats_flat() {
+        let stats = distance_stats("qqq", ["abc", "def", "ghi"].iter());
+        assert_eq!(stats.separation(), Some(0.0));
+
+        let stats = distance_stats("abc", std::iter::empty::<&str>());
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.percentile(50.0), None);
+    }
+
+    #[test]
+    fn test_distance_histogram() {
+        let candidates = ["print", "format", "write"];
+        let lookups = ["print", "prnt", "fromat", "wirte", "xyzzy_plugh"];
+        let histogram = distance_histogram(lookups, candidates.iter());
+        assert_eq!(histogram.samples(), 5);
+        assert_eq!(histogram.counts()[..3], [1, 1, 2]);
+        assert_eq!(histogram.within(1), 2);
+        assert_eq!(histogram.within(usize::MAX), 5);
+        assert_eq!(histogram.within_ratio(1.0 / 3.0), 3);
+
+        let histogram = distance_histogram(lookups, std::iter::empty::<&str>());
+        assert_eq!(histogram.samples(), 0);
+        assert_eq!(histogram.within(3), 0);
+    }
+}
@@ -0,0 +1,149 @@
+//! Sublime Text's fuzzy-match scoring.
+//!
+//! This reproduces the scoring of Sublime Text's "Goto Anything", as
+//! reverse-engineered by Forrest Smith, so that tooling built on it orders
+//! results the way Sublime users expect.
+
+const SEQUENTIAL_BONUS: i32 = 15;
+const SEPARATOR_BONUS: i32 = 30;
+const CAMEL_BONUS: i32 = 30;
+const FIRST_LETTER_BONUS: i32 = 15;
+const LEADING_LETTER_PENALTY: i32 = -5;
+const MAX_LEADING_LETTER_PENALTY: i32 = -15;
+const UNMATCHED_LETTER_PENALTY: i32 = -1;
+
+const RECURSION_LIMIT: usize = 10;
+const MAX_MATCHES: usize = 256;
+
+/// Scores `candidate` against `pattern` like Sublime Text does, or returns
+/// `None` if `pattern` is not a case-insensitive subsequence of `candidate`.
+///
+/// Higher is better. Every candidate starts at 100, gains bonuses for
+/// consecutive hits, hits after separators and on camelCase humps, and loses
+/// points for letters before the first hit and for unmatched letters. An
+/// empty pattern matches everything with a score of 0.
+pub fn sublime_score(pattern: &str, candidate: &str) -> Option<i32> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut recursions = 0;
+    let mut matches = Vec::new();
+    fuzzy_match(&pattern, &candidate, 0, &[], &mut matches, &mut recursions)
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Matches `pattern` against `candidate[start..]`, after the hits already in
+/// `prior`, trying every alternative position of each hit up to the
+/// recursion limit and keeping the best-scoring one in `matches`.
+fn fuzzy_match(
+    pattern: &[char],
+    candidate: &[char],
+    start: usize,
+    prior: &[usize],
+    matches: &mut Vec<usize>,
+    recursions: &mut usize,
+) -> Option<i32> {
+    *recursions += 1;
+    if *recursions >= RECURSION_LIMIT || pattern.is_empty() || start >= candidate.len() {
+        return None;
+    }
+
+    matches.clear();
+    matches.extend_from_slice(prior);
+    let mut best_recursive: Option<(i32, Vec<usize>)> = None;
+    let mut pi = 0;
+    for si in start..candidate.len() {
+        if pi == pattern.len() {
+            break;
+        }
+        if !eq_ignore_case(pattern[pi], candidate[si]) {
+            continue;
+        }
+        if matches.len() >= MAX_MATCHES {
+            return None;
+        }
+        // Try skipping this hit in favor of a later one.
+        let mut recursive_matches = Vec::new();
+        if let Some(score) = fuzzy_match(
+            &pattern[pi..],
+            candidate,
+            si + 1,
+            matches,
+            &mut recursive_matches,
+            recursions,
+        ) {
+            if best_recursive
+                .as_ref()
+                .is_none_or(|&(best, _)| score > best)
+            {
+                best_recursive = Some((score, recursive_matches));
+            }
+        }
+        matches.push(si);
+        pi += 1;
+    }
+
+    let score = (pi == pattern.len()).then(|| score(candidate, matches));
+    match (score, best_recursive) {
+        (score, Some((recursive, recursive_matches)))
+            if score.is_none_or(|score| recursive > score) =>
+        {
+            *matches = recursive_matches;
+            Some(recursive)
+        }
+        (score, _) => score,
+    }
+}
+
+fn score(candidate: &[char], matches: &[usize]) -> i32 {
+    let mut score = 100;
+    score += (LEADING_LETTER_PENALTY * matches[0] as i32).max(MAX_LEADING_LETTER_PENALTY);
+    score += UNMATCHED_LETTER_PENALTY * (candidate.len() - matches.len()) as i32;
+    for (i, &curr) in matches.iter().enumerate() {
+        if i > 0 && curr == matches[i - 1] + 1 {
+            score += SEQUENTIAL_BONUS;
+        }
+        if curr == 0 {
+            score += FIRST_LETTER_BONUS;
+            continue;
+        }
+        let neighbor = candidate[curr - 1];
+        if neighbor.is_lowercase() && candidate[curr].is_uppercase() {
+            score += CAMEL_BONUS;
+        }
+        if neighbor == '_' || neighbor == ' ' {
+            score += SEPARATOR_BONUS;
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sublime_score() {
+        assert_eq!(sublime_score("", "abc"), Some(0));
+        assert_eq!(sublime_score("", ""), Some(0));
+        assert_eq!(sublime_score("a", ""), None);
+        assert_eq!(sublime_score("abd", "abc"), None);
+        // 100 + first letter + 2 * sequential.
+        assert_eq!(sublime_score("abc", "abc"), Some(145));
+        // 100 + first letter + camel - 2 unmatched letters.
+        assert_eq!(sublime_score("gF", "getFoo"), Some(141));
+        // The leading letter penalty is capped.
+        assert_eq!(sublime_score("z", "abcdz"), Some(100 - 15 - 4));
+    }
+
+    #[test]
+    fn test_prefers_best_alternative() {
+        // The greedy match `xAxB_ab` scores 90; `xaxb_AB` scores 125.
+        assert_eq!(sublime_score("ab", "xaxb_ab"), Some(125));
+    }
+}
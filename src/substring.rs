@@ -0,0 +1,180 @@
+//! Matching that also credits candidates containing the lookup, as in
+//! rustc's `find_best_match_for_name_with_substrings`.
+
+use crate::{
+    eq_ignore_case, find_best_match_position, find_match_by_sorted_words, kernel, Threshold,
+    WordSplit,
+};
+
+/// The shortest lookup, in chars, that the containment tier of
+/// [`find_best_match_for_name_with_substrings`] applies to; shorter ones are
+/// contained in too many candidates to be a useful hint.
+pub const MIN_SUBSTRING_LOOKUP_LEN: usize = 3;
+
+/// Finds the edit distance between `a` and `b` with the length difference
+/// discounted, so that substrings score low, if it is at most `limit`.
+///
+/// A string strictly contained in the other scores 1 unless one is more than
+/// twice as long as the other, in which case the full length difference
+/// counts again. Otherwise, half of the length difference is charged.
+pub fn lev_distance_with_substrings(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let n = a.chars().count();
+    let m = b.chars().count();
+    // One string is less than half as long as the other.
+    let big_len_diff = n * 2 < m || m * 2 < n;
+    let len_diff = n.abs_diff(m);
+    let limit_with_len_diff = limit.saturating_add(len_diff);
+    let distance = kernel::distance(a, b, Some(limit_with_len_diff));
+    if distance > limit_with_len_diff {
+        return None;
+    }
+
+    // Exact substring matches now score 0.
+    let score = distance - len_diff;
+    let score = if score == 0 && len_diff > 0 && !big_len_diff {
+        // A substring, but not the whole word.
+        1
+    } else if !big_len_diff {
+        score + len_diff.div_ceil(2)
+    } else {
+        score + len_diff
+    };
+    (score <= limit).then_some(score)
+}
+
+/// Like [`find_best_match_for_name`](crate::find_best_match_for_name), also
+/// crediting candidates that contain the lookup.
+///
+/// The edit distance tier scores with [`lev_distance_with_substrings`], and
+/// ties among its best candidates are broken by the plain edit distance. If
+/// neither that tier nor the sorted-words tier finds anything, the shortest
+/// candidate containing the lookup wins, so `vec` suggests `my_vec_of_items`.
+pub fn find_best_match_for_name_with_substrings<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<String>
+where
+    T: AsRef<str>,
+{
+    let candidates: Vec<T> = iter_names.collect();
+    find_with_substrings(&candidates, lookup, dist).map(|candidate| candidate.as_ref().to_string())
+}
+
+fn find_with_substrings<'a, T>(
+    candidates: &'a [T],
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<&'a T>
+where
+    T: AsRef<str>,
+{
+    // 1. Exact match
+    let exact = candidates.iter().find(|c| c.as_ref() == lookup);
+    if let Some(candidate) = exact.or_else(|| {
+        candidates
+            .iter()
+            .find(|c| eq_ignore_case(c.as_ref(), lookup))
+    }) {
+        return Some(candidate);
+    }
+
+    // 2. Edit distance match with substrings, keeping every candidate at the
+    // best distance so far
+    let mut dist = Threshold::from(dist).max_dist(lookup);
+    let mut best: Vec<&T> = Vec::new();
+    for candidate in candidates {
+        match lev_distance_with_substrings(lookup, candidate.as_ref(), dist) {
+            Some(0) => return Some(candidate),
+            Some(d) => {
+                if d < dist {
+                    dist = d;
+                    best.clear();
+                }
+                best.push(candidate);
+            }
+            None => {}
+        }
+    }
+    let best = match best[..] {
+        [] => None,
+        [candidate] => Some(candidate),
+        // A tie; select among them ignoring substrings.
+        _ => find_best_match_position(best.iter(), lookup, Some(lookup.len())).map(|i| best[i]),
+    };
+
+    // 3. Sorted word match
+    best.or_else(|| find_match_by_sorted_words(candidates.iter(), lookup, WordSplit::new()))
+        // 4. Containment
+        .or_else(|| {
+            if lookup.chars().count() < MIN_SUBSTRING_LOOKUP_LEN {
+                return None;
+            }
+            candidates
+                .iter()
+                .filter(|c| c.as_ref().contains(lookup))
+                .min_by_key(|c| c.as_ref().len())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lev_distance_with_substrings() {
+        assert_eq!(lev_distance_with_substrings("abc", "abc", 1), Some(0));
+        assert_eq!(lev_distance_with_substrings("abc", "abcd", 1), Some(1));
+        assert_eq!(lev_distance_with_substrings("abcd", "abc", 1), Some(1));
+        assert_eq!(lev_distance_with_substrings("abc", "xbcdef", 3), Some(3));
+        // More than twice as long.
+        assert_eq!(
+            lev_distance_with_substrings("vec", "my_vec_of_items", 3),
+            None
+        );
+        assert_eq!(
+            lev_distance_with_substrings("vec", "my_vec_of_items", 12),
+            Some(12)
+        );
+        assert_eq!(
+            lev_distance_with_substrings("ab", "abc", usize::MAX),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_find_best_match_for_name_with_substrings() {
+        let find = |input: &[&str], lookup| {
+            find_best_match_for_name_with_substrings(input.iter(), lookup, None)
+        };
+        assert_eq!(find(&["aaab", "aaabc"], "aaaa"), Some("aaab".to_string()));
+        assert_eq!(find(&["AAAA"], "aaaa"), Some("AAAA".to_string()));
+        assert_eq!(
+            find(&["a_b_c", "c_b_a"], "b_a_c"),
+            Some("c_b_a".to_string())
+        );
+        // Plain edit distance breaks the tie between the two substrings.
+        assert_eq!(
+            find(&["item_lengths", "items_len"], "item_len"),
+            Some("items_len".to_string())
+        );
+        assert_eq!(
+            find(
+                &["my_vec_of_items", "vec_of_items_and_more", "deque"],
+                "vec"
+            ),
+            Some("my_vec_of_items".to_string())
+        );
+        assert_eq!(find(&["my_vec_of_items"], "ve"), None);
+        // The default threshold is the same as elsewhere.
+        assert_eq!(
+            find(&["grösse"], "größe"),
+            crate::find_best_match_for_name(["grösse"].iter(), "größe", None)
+        );
+        let long = "abcdefghij".repeat(10);
+        assert_eq!(
+            find_best_match_for_name_with_substrings(["xyz", &long].iter(), "ab", Some(usize::MAX)),
+            Some("xyz".to_string())
+        );
+    }
+}
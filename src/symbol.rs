@@ -0,0 +1,133 @@
+//! Interned symbols, as used by rustc.
+//!
+//! Compilers and similar tools already intern their identifiers and compare
+//! them as integers. Matching over [`Symbol`]s lets them keep doing so: the
+//! candidates are resolved to their names without hashing or allocating, and
+//! the winner is handed back as a `Symbol` rather than a fresh `String`.
+
+use std::collections::HashMap;
+
+use crate::find_best_match;
+
+/// An interned string, cheap to copy and compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The index of this symbol in its interner.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// A string interner handing out [`Symbol`]s.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    names: Vec<Box<str>>,
+    symbols: HashMap<Box<str>, Symbol>,
+}
+
+/// A symbol paired with its name, so that it can go through the matching
+/// pipeline.
+struct Named<'a>(Symbol, &'a str);
+
+impl AsRef<str> for Named<'_> {
+    fn as_ref(&self) -> &str {
+        self.1
+    }
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Interns `name`, returning the existing symbol if it was interned
+    /// before.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name.into());
+        self.symbols.insert(name.into(), symbol);
+        symbol
+    }
+
+    /// Returns the symbol for `name` if it has been interned.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// Returns the name of `symbol`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` was created by a different interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.0 as usize]
+    }
+
+    /// The number of interned symbols.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether no symbols have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Finds the best match for `lookup` among `candidates`, like
+    /// [`find_best_match_for_name`](crate::find_best_match_for_name).
+    pub fn find_best_match_for_name(
+        &self,
+        candidates: impl Iterator<Item = Symbol>,
+        lookup: Symbol,
+        dist: Option<usize>,
+    ) -> Option<Symbol> {
+        let candidates = candidates.map(|symbol| Named(symbol, self.resolve(symbol)));
+        find_best_match(candidates, self.resolve(lookup), dist).map(|Named(symbol, _)| symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::new();
+        let foo = interner.intern("foo");
+        assert_eq!(interner.intern("foo"), foo);
+        assert_ne!(interner.intern("bar"), foo);
+        assert_eq!(interner.resolve(foo), "foo");
+        assert_eq!(interner.get("bar").map(Symbol::as_u32), Some(1));
+        assert_eq!(interner.get("baz"), None);
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn test_find_best_match_for_name() {
+        let mut interner = Interner::new();
+        let candidates: Vec<Symbol> = ["aaab", "aaabc", "AAAA"]
+            .iter()
+            .map(|name| interner.intern(name))
+            .collect();
+        let lookup = interner.intern("aaaa");
+        assert_eq!(
+            interner.find_best_match_for_name(candidates.iter().copied(), lookup, None),
+            Some(candidates[2])
+        );
+        assert_eq!(
+            interner.find_best_match_for_name(candidates[..2].iter().copied(), lookup, None),
+            Some(candidates[0])
+        );
+        let lookup = interner.intern("1111111111");
+        assert_eq!(
+            interner.find_best_match_for_name(candidates.iter().copied(), lookup, None),
+            None
+        );
+    }
+}
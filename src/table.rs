@@ -0,0 +1,25 @@
+//! Lookups in the built-in static tables.
+
+use crate::{lev_distance, Threshold};
+
+/// Finds the entry of `table` closest to `lookup`, ignoring ASCII case.
+///
+/// Returns `None` if `lookup` is already in the table or if no entry is
+/// within one-third of its length.
+pub(crate) fn suggest_ignore_ascii_case(
+    table: &[&'static str],
+    lookup: &str,
+) -> Option<&'static str> {
+    if table.iter().any(|entry| entry.eq_ignore_ascii_case(lookup)) {
+        return None;
+    }
+
+    let lookup = lookup.to_ascii_lowercase();
+    let max_dist = Threshold::OneThird.max_dist(&lookup);
+    table
+        .iter()
+        .map(|entry| (*entry, lev_distance(&lookup, &entry.to_ascii_lowercase())))
+        .filter(|&(_, dist)| dist <= max_dist)
+        .min_by_key(|&(_, dist)| dist)
+        .map(|(entry, _)| entry)
+}
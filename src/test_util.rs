@@ -0,0 +1,37 @@
+//! Helpers shared by the tests of the indexes.
+
+use crate::{lev_distance, Distance};
+
+/// Every word of `words` with its distance to `query`, closest first; words
+/// at the same distance keep their order.
+pub(crate) fn scan<'a>(words: &[&'a str], query: &str) -> Vec<(&'a str, Distance)> {
+    let mut scanned: Vec<(&str, Distance)> = words
+        .iter()
+        .map(|&word| (word, Distance(lev_distance(query, word))))
+        .collect();
+    scanned.sort_by_key(|&(_, dist)| dist);
+    scanned
+}
+
+/// Checks that `find_within` finds the same words as a linear scan of
+/// `words`, for every query and every maximum distance up to `max_dist`.
+pub(crate) fn check_find_within<'a>(
+    words: &[&'a str],
+    queries: &[&str],
+    max_dist: usize,
+    find_within: impl Fn(&str, usize) -> Vec<(&'a str, Distance)>,
+) {
+    for &query in queries {
+        for max_dist in 0..=max_dist {
+            let mut expected = scan(words, query);
+            expected.retain(|&(_, dist)| dist.within(max_dist));
+            assert_eq!(
+                find_within(query, max_dist),
+                expected,
+                "{:?} {}",
+                query,
+                max_dist
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Strategies for the maximum allowable edit distance.
+
+use std::cmp;
+
+use crate::adaptive_max_dist;
+
+/// How the maximum allowable edit distance follows from the lookup.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Threshold {
+    /// A fixed number of edits, whatever the lookup.
+    Absolute(usize),
+    /// A fraction of the lookup's length in chars, rounded down, so that
+    /// `Ratio(0.25)` accepts matches within 25% of the lookup.
+    Ratio(f64),
+    /// One-third of the lookup's length in bytes, but at least one edit, as
+    /// in [`find_best_match_for_name`](crate::find_best_match_for_name).
+    ///
+    /// Bytes are counted for compatibility with rustc, so non-ASCII lookups
+    /// get a little more leeway than under [`Threshold::Ratio`], which counts
+    /// chars. For a threshold that also depends on the candidates, see
+    /// [`Threshold::Adaptive`].
+    #[default]
+    OneThird,
+    /// Like [`Threshold::OneThird`], capped at half of the minimum distance
+    /// between two candidates as in
+    /// [`adaptive_max_dist`](crate::adaptive_max_dist).
+    ///
+    /// The same as [`Threshold::OneThird`] without the candidates at hand,
+    /// as in [`Threshold::max_dist`].
+    Adaptive,
+}
+
+impl Threshold {
+    /// The maximum allowable edit distance for `lookup`.
+    pub fn max_dist(self, lookup: &str) -> usize {
+        match self {
+            Threshold::Absolute(max_dist) => max_dist,
+            Threshold::Ratio(ratio) => (lookup.chars().count() as f64 * ratio.max(0.0)) as usize,
+            Threshold::OneThird | Threshold::Adaptive => cmp::max(lookup.len(), 3) / 3,
+        }
+    }
+
+    /// The maximum allowable edit distance for `lookup` among `candidates`,
+    /// which only [`Threshold::Adaptive`] looks at.
+    pub fn max_dist_among<T>(self, candidates: impl Iterator<Item = T>, lookup: &str) -> usize
+    where
+        T: AsRef<str>,
+    {
+        match self {
+            Threshold::Adaptive => adaptive_max_dist(candidates, lookup),
+            threshold => threshold.max_dist(lookup),
+        }
+    }
+}
+
+/// `Some` maximum distance is [`Threshold::Absolute`], and `None` is
+/// [`Threshold::OneThird`], as in the `dist` argument of the free functions.
+impl From<Option<usize>> for Threshold {
+    fn from(dist: Option<usize>) -> Self {
+        dist.map_or(Threshold::OneThird, Threshold::Absolute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold() {
+        let lookups = ["", "ab", "abcdef", "größe", "a_longer_name"];
+        let max_dists: Vec<_> = lookups
+            .iter()
+            .map(|l| {
+                [
+                    Threshold::Absolute(2).max_dist(l),
+                    Threshold::Ratio(0.25).max_dist(l),
+                    Threshold::OneThird.max_dist(l),
+                    Threshold::Adaptive.max_dist(l),
+                ]
+            })
+            .collect();
+        assert_eq!(
+            max_dists,
+            [
+                [2, 0, 1, 1],
+                [2, 0, 1, 1],
+                [2, 1, 2, 2],
+                [2, 1, 2, 2],
+                [2, 3, 4, 4]
+            ]
+        );
+        assert_eq!(Threshold::from(Some(3)), Threshold::Absolute(3));
+        assert_eq!(Threshold::from(None), Threshold::OneThird);
+    }
+
+    #[test]
+    fn test_max_dist_among() {
+        let input = ["read_file", "load_file"];
+        assert_eq!(
+            Threshold::OneThird.max_dist_among(input.iter(), "rexd_fxle"),
+            3
+        );
+        assert_eq!(
+            Threshold::Adaptive.max_dist_among(input.iter(), "rexd_fxle"),
+            1
+        );
+        assert_eq!(
+            Threshold::Absolute(5).max_dist_among(input.iter(), "rexd_fxle"),
+            5
+        );
+    }
+}
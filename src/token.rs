@@ -0,0 +1,143 @@
+//! Similarities over the words of a string.
+
+use std::cmp;
+use std::collections::BTreeSet;
+
+use crate::{kernel, CaseFolding, WordSplit};
+
+/// Splits `s` into words at whitespace and underscores.
+fn words(s: &str) -> Vec<&str> {
+    s.split(|c: char| c.is_whitespace() || c == '_')
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// One minus the Levenshtein distance relative to the longer string.
+fn ratio(a: &str, b: &str) -> f64 {
+    let len = cmp::max(a.chars().count(), b.chars().count());
+    if len == 0 {
+        return 1.0;
+    }
+    1.0 - kernel::distance(a, b, None) as f64 / len as f64
+}
+
+/// The token set ratio in `0.0..=1.0`, in the spirit of fuzzywuzzy's
+/// `token_set_ratio`: the words shared by both strings are compared against
+/// each string's full set of words, so extra or reordered words cost little.
+pub(crate) fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let mut a_words = words(a);
+    let mut b_words = words(b);
+    a_words.sort_unstable();
+    a_words.dedup();
+    b_words.sort_unstable();
+    b_words.dedup();
+
+    let common: Vec<&str> = a_words
+        .iter()
+        .filter(|word| b_words.binary_search(word).is_ok())
+        .copied()
+        .collect();
+    let with_rest = |words: &[&str]| {
+        let rest = words
+            .iter()
+            .filter(|word| common.binary_search(word).is_err());
+        common
+            .iter()
+            .chain(rest)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let common_joined = common.join(" ");
+    let a_joined = with_rest(&a_words);
+    let b_joined = with_rest(&b_words);
+    if common.is_empty() {
+        return ratio(&a_joined, &b_joined);
+    }
+    [
+        ratio(&common_joined, &a_joined),
+        ratio(&common_joined, &b_joined),
+        ratio(&a_joined, &b_joined),
+    ]
+    .into_iter()
+    .fold(0.0, f64::max)
+}
+
+/// Finds the Levenshtein distance between two strings counting whole
+/// words, so that inserting, deleting or replacing a word costs one however
+/// long it is.
+///
+/// Words are separated by whitespace, and compared exactly. For other
+/// tokenizers, use [`lev_distance_slices`](crate::lev_distance_slices).
+pub fn lev_distance_words(a: &str, b: &str) -> usize {
+    let a: Vec<&str> = a.split_whitespace().collect();
+    let b: Vec<&str> = b.split_whitespace().collect();
+    kernel::slice(&a, &b)
+}
+
+/// Finds the Jaccard similarity of the words of two strings in
+/// `0.0..=1.0`: the distinct words both have over the distinct words either
+/// has, so `1.0` means the same set of words.
+///
+/// Words are split by `split`, and compared ignoring case if it splits at
+/// camelCase boundaries. Unlike character-level metrics, it is not misled
+/// by long shared words, which suits error messages and tag lists. Two
+/// strings without words have a similarity of `1.0`.
+pub fn token_jaccard(a: &str, b: &str, split: WordSplit) -> f64 {
+    let set = |s| -> BTreeSet<String> {
+        split
+            .words(s)
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                if split.ignores_case() {
+                    CaseFolding::Unicode.folded(word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect()
+    };
+    let (a, b) = (set(a), set(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_set_ratio() {
+        assert_eq!(token_set_ratio("new york mets", "mets new york"), 1.0);
+        assert_eq!(token_set_ratio("max_dist", "max_dist_limit"), 1.0);
+        assert_eq!(token_set_ratio("abc", "xyz"), 0.0);
+        assert_eq!(token_set_ratio("", ""), 1.0);
+    }
+
+    #[test]
+    fn test_lev_distance_words() {
+        assert_eq!(lev_distance_words("git commit --amend", "git commit"), 1);
+        assert_eq!(
+            lev_distance_words("cargo build --release", "cargo  test --release"),
+            1
+        );
+        assert_eq!(lev_distance_words("a b c", "c b a"), 2);
+        assert_eq!(lev_distance_words("", "one two"), 2);
+    }
+
+    #[test]
+    fn test_token_jaccard() {
+        let split = WordSplit::new().whitespace(true);
+        assert_eq!(
+            token_jaccard("file not found", "not found: file", split),
+            0.5
+        );
+        assert_eq!(token_jaccard("a  b b", "b a", split), 1.0);
+        assert_eq!(token_jaccard("Rust", "rust", split), 0.0);
+        assert_eq!(token_jaccard("Rust", "rust", WordSplit::all()), 1.0);
+        assert_eq!(token_jaccard("", "  ", split), 1.0);
+    }
+}
@@ -0,0 +1,155 @@
+//! A trie of candidates searched with a Levenshtein automaton.
+//!
+//! Candidates sharing a prefix share the trie path, so the DP row for the
+//! prefix is computed once for all of them, and the whole subtree is pruned
+//! as soon as the row can no longer match.
+
+use crate::{Distance, LevenshteinAutomaton};
+
+/// A dictionary searched by walking a trie.
+#[derive(Clone, Debug)]
+pub struct FuzzyTrie {
+    nodes: Vec<TrieNode>,
+    words: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    /// The children, sorted by their char.
+    children: Vec<(char, usize)>,
+    /// The index in `words` of the word ending here, if any.
+    word: Option<usize>,
+}
+
+impl Default for FuzzyTrie {
+    fn default() -> Self {
+        FuzzyTrie {
+            nodes: vec![TrieNode::default()],
+            words: Vec::new(),
+        }
+    }
+}
+
+impl FuzzyTrie {
+    /// Creates an empty trie.
+    pub fn new() -> Self {
+        FuzzyTrie::default()
+    }
+
+    /// The number of words in the trie.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the trie holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Inserts `word`, returning whether it was not already in the trie.
+    pub fn insert(&mut self, word: impl Into<String>) -> bool {
+        let word = word.into();
+        let mut node = 0;
+        for c in word.chars() {
+            let children = &self.nodes[node].children;
+            node = match children.binary_search_by_key(&c, |&(k, _)| k) {
+                Ok(i) => children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes[node].children.insert(i, (c, child));
+                    self.nodes.push(TrieNode::default());
+                    child
+                }
+            };
+        }
+        if self.nodes[node].word.is_some() {
+            return false;
+        }
+        self.nodes[node].word = Some(self.words.len());
+        self.words.push(word);
+        true
+    }
+
+    /// Finds every word within `max_dist` of `query`, closest first; words at
+    /// the same distance keep their insertion order.
+    pub fn find_within(&self, query: &str, max_dist: usize) -> Vec<(&str, Distance)> {
+        let automaton = LevenshteinAutomaton::new(query, max_dist);
+        let mut found: Vec<(Distance, usize)> = Vec::new();
+        let mut stack = vec![(0, automaton.start())];
+        while let Some((node, state)) = stack.pop() {
+            let TrieNode { children, word } = &self.nodes[node];
+            if let (Some(word), Some(dist)) = (word, automaton.distance(&state)) {
+                found.push((dist, *word));
+            }
+            for &(c, child) in children {
+                let next = automaton.step(&state, c);
+                if automaton.can_match(&next) {
+                    stack.push((child, next));
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|(dist, word)| (self.words[word].as_str(), dist))
+            .collect()
+    }
+}
+
+impl<S: Into<String>> Extend<S> for FuzzyTrie {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        for word in words {
+            self.insert(word);
+        }
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for FuzzyTrie {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> Self {
+        let mut trie = FuzzyTrie::new();
+        trie.extend(words);
+        trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::check_find_within;
+
+    const WORDS: &[&str] = &[
+        "std::collections::HashMap",
+        "std::collections::HashSet",
+        "std::collections::BTreeMap",
+        "std::cell::RefCell",
+        "std::cell::Cell",
+        "über",
+        "",
+    ];
+
+    #[test]
+    fn test_find_within() {
+        let trie: FuzzyTrie = WORDS.iter().copied().collect();
+        assert_eq!(trie.len(), WORDS.len());
+        let queries = [
+            "std::collections::HashMop",
+            "std::cell::Cel",
+            "uber",
+            "",
+            "zzz",
+        ];
+        check_find_within(WORDS, &queries, 3, |query, max_dist| {
+            trie.find_within(query, max_dist)
+        });
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut trie = FuzzyTrie::new();
+        assert!(trie.is_empty());
+        assert!(trie.insert("ab"));
+        assert!(trie.insert("a"));
+        assert!(!trie.insert("ab"));
+        assert_eq!(trie.find_within("a", 0), vec![("a", Distance(0))]);
+    }
+}
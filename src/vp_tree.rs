@@ -0,0 +1,245 @@
+//! A vantage-point tree over any metric.
+//!
+//! Each node splits the words below it by their distance to its own word,
+//! the vantage point, at the median `mu`: closer words go inside, farther
+//! ones outside. By the triangle inequality, a query at distance `d` from
+//! the vantage point only needs to look inside if `d - r <= mu`, and outside
+//! if `d + r >= mu`, for a search radius `r`. Unlike a
+//! [`BkTree`](crate::BkTree), the distances need not be integers.
+
+use std::cmp::Ordering;
+
+/// A metric tree of words, built once and queried many times.
+///
+/// `metric` must be a true metric, satisfying the triangle inequality, such
+/// as the Levenshtein distance or its normalized form; otherwise matches
+/// may be missed.
+#[derive(Clone, Debug)]
+pub struct VpTree<F> {
+    words: Vec<String>,
+    nodes: Vec<VpNode>,
+    metric: F,
+}
+
+#[derive(Clone, Debug)]
+struct VpNode {
+    /// The index in `words` of the vantage point.
+    word: usize,
+    mu: f64,
+    inside: Option<usize>,
+    outside: Option<usize>,
+}
+
+impl<F> VpTree<F>
+where
+    F: Fn(&str, &str) -> f64,
+{
+    /// Builds a tree of `words` under `metric`.
+    pub fn new<T>(words: impl IntoIterator<Item = T>, metric: F) -> Self
+    where
+        T: Into<String>,
+    {
+        let words: Vec<String> = words.into_iter().map(Into::into).collect();
+        let mut tree = VpTree {
+            nodes: Vec::with_capacity(words.len()),
+            words,
+            metric,
+        };
+        let mut items: Vec<(usize, f64)> = (0..tree.words.len()).map(|i| (i, 0.0)).collect();
+        tree.build(&mut items);
+        tree
+    }
+
+    /// The number of words in the tree.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the tree holds no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Builds the subtree of `items`, returning the index of its root.
+    fn build(&mut self, items: &mut [(usize, f64)]) -> Option<usize> {
+        let (&mut (word, _), rest) = items.split_first_mut()?;
+        for item in rest.iter_mut() {
+            item.1 = (self.metric)(&self.words[word], &self.words[item.0]);
+        }
+        let median = rest.len() / 2;
+        let mu = if rest.is_empty() {
+            0.0
+        } else {
+            rest.select_nth_unstable_by(median, |a, b| a.1.total_cmp(&b.1));
+            rest[median].1
+        };
+        let node = self.nodes.len();
+        self.nodes.push(VpNode {
+            word,
+            mu,
+            inside: None,
+            outside: None,
+        });
+        let (inside, outside) = rest.split_at_mut(median);
+        self.nodes[node].inside = self.build(inside);
+        self.nodes[node].outside = self.build(outside);
+        Some(node)
+    }
+
+    /// Finds every word within `r` of `query`, closest first; words at the
+    /// same distance keep their original order.
+    pub fn within(&self, query: &str, r: f64) -> Vec<(&str, f64)> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = self.root().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let VpNode {
+                word,
+                mu,
+                inside,
+                outside,
+            } = self.nodes[node];
+            let d = (self.metric)(query, &self.words[word]);
+            if d <= r {
+                found.push((word, d));
+            }
+            if d - r <= mu {
+                stack.extend(inside);
+            }
+            if d + r >= mu {
+                stack.extend(outside);
+            }
+        }
+        self.resolve(found)
+    }
+
+    /// Finds the `k` words closest to `query`, closest first; words at the
+    /// same distance keep their original order.
+    pub fn nearest(&self, query: &str, k: usize) -> Vec<(&str, f64)> {
+        let mut nearest = Vec::with_capacity(k);
+        if k > 0 {
+            self.search(self.root(), query, k, &mut nearest);
+        }
+        self.resolve(nearest)
+    }
+
+    fn root(&self) -> Option<usize> {
+        (!self.nodes.is_empty()).then_some(0)
+    }
+
+    /// Adds the words under `node` closer than the `k`th in `nearest` to it.
+    fn search(&self, node: Option<usize>, query: &str, k: usize, nearest: &mut Vec<(usize, f64)>) {
+        let Some(node) = node else {
+            return;
+        };
+        let VpNode {
+            word,
+            mu,
+            inside,
+            outside,
+        } = self.nodes[node];
+        let d = (self.metric)(query, &self.words[word]);
+        let tau = |nearest: &Vec<(usize, f64)>| {
+            if nearest.len() < k {
+                f64::INFINITY
+            } else {
+                nearest[k - 1].1
+            }
+        };
+        if nearest.len() < k || by_distance(&(word, d), &nearest[k - 1]) == Ordering::Less {
+            let pos =
+                nearest.partition_point(|item| by_distance(item, &(word, d)) == Ordering::Less);
+            nearest.insert(pos, (word, d));
+            nearest.truncate(k);
+        }
+        // Search the side the query falls on first, which tightens `tau`.
+        if d < mu {
+            self.search(inside, query, k, nearest);
+            if d + tau(nearest) >= mu {
+                self.search(outside, query, k, nearest);
+            }
+        } else {
+            self.search(outside, query, k, nearest);
+            if d - tau(nearest) <= mu {
+                self.search(inside, query, k, nearest);
+            }
+        }
+    }
+
+    fn resolve(&self, mut found: Vec<(usize, f64)>) -> Vec<(&str, f64)> {
+        found.sort_unstable_by(by_distance);
+        found
+            .into_iter()
+            .map(|(word, d)| (self.words[word].as_str(), d))
+            .collect()
+    }
+}
+
+/// Orders `(word, distance)` pairs by distance, then by word index.
+fn by_distance(a: &(usize, f64), b: &(usize, f64)) -> Ordering {
+    a.1.total_cmp(&b.1).then(a.0.cmp(&b.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    const WORDS: &[&str] = &[
+        "book", "books", "cake", "boo", "boon", "cook", "cape", "cart", "print", "println", "",
+    ];
+
+    fn normalized(a: &str, b: &str) -> f64 {
+        let len = a.chars().count().max(b.chars().count());
+        if len == 0 {
+            0.0
+        } else {
+            lev_distance(a, b) as f64 / len as f64
+        }
+    }
+
+    fn brute_force(query: &str) -> Vec<(&'static str, f64)> {
+        let mut all: Vec<(usize, f64)> = WORDS
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (i, lev_distance(query, w) as f64))
+            .collect();
+        all.sort_by(by_distance);
+        all.into_iter().map(|(i, d)| (WORDS[i], d)).collect()
+    }
+
+    #[test]
+    fn test_vp_tree() {
+        let tree = VpTree::new(WORDS.iter().copied(), |a, b| lev_distance(a, b) as f64);
+        assert_eq!(tree.len(), WORDS.len());
+        for query in ["bok", "cak", "prnt", "", "zzzzzzzz"] {
+            let all = brute_force(query);
+            for k in [0, 1, 3, 20] {
+                let expected: Vec<_> = all.iter().copied().take(k).collect();
+                assert_eq!(tree.nearest(query, k), expected, "{:?}", query);
+            }
+            for r in [0.0, 1.0, 2.5] {
+                let expected: Vec<_> = all.iter().copied().filter(|&(_, d)| d <= r).collect();
+                assert_eq!(tree.within(query, r), expected, "{:?}", query);
+            }
+        }
+    }
+
+    #[test]
+    fn test_float_metric() {
+        let tree = VpTree::new(WORDS.iter().copied(), normalized);
+        assert_eq!(
+            tree.within("book", 0.25),
+            vec![
+                ("book", 0.0),
+                ("books", 0.2),
+                ("boo", 0.25),
+                ("boon", 0.25),
+                ("cook", 0.25)
+            ]
+        );
+        assert_eq!(tree.nearest("printl", 1), vec![("println", 1.0 / 7.0)]);
+        assert!(VpTree::new(Vec::<String>::new(), normalized)
+            .nearest("a", 3)
+            .is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Levenshtein distances with per-operation costs.
+
+use std::cmp;
+
+use crate::Keyboard;
+
+/// A Levenshtein distance where insertions, deletions, and substitutions
+/// each have their own cost.
+///
+/// Every cost defaults to 1, which gives the plain Levenshtein distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WeightedLevenshtein {
+    insertion: usize,
+    deletion: usize,
+    substitution: usize,
+    keyboard: Option<(Keyboard, usize)>,
+}
+
+impl Default for WeightedLevenshtein {
+    fn default() -> Self {
+        WeightedLevenshtein {
+            insertion: 1,
+            deletion: 1,
+            substitution: 1,
+            keyboard: None,
+        }
+    }
+}
+
+impl WeightedLevenshtein {
+    /// Creates a metric where every operation costs 1.
+    pub fn new() -> Self {
+        WeightedLevenshtein::default()
+    }
+
+    /// Sets the cost of inserting a char of `b` into `a`.
+    pub fn insertion(mut self, cost: usize) -> Self {
+        self.insertion = cost;
+        self
+    }
+
+    /// Sets the cost of deleting a char of `a`.
+    pub fn deletion(mut self, cost: usize) -> Self {
+        self.deletion = cost;
+        self
+    }
+
+    /// Sets the cost of replacing a char of `a` by a char of `b`.
+    pub fn substitution(mut self, cost: usize) -> Self {
+        self.substitution = cost;
+        self
+    }
+
+    /// Makes substituting a char by one on a neighbouring key of `keyboard`
+    /// cost `cost` instead of the substitution cost, so that slips of the
+    /// finger rank above other typos.
+    pub fn keyboard(mut self, keyboard: Keyboard, cost: usize) -> Self {
+        self.keyboard = Some((keyboard, cost));
+        self
+    }
+
+    /// The cost of replacing `a` by `b`.
+    fn substitution_cost(&self, a: char, b: char) -> usize {
+        match self.keyboard {
+            Some((keyboard, cost)) if keyboard.adjacent(a, b) => cost,
+            _ => self.substitution,
+        }
+    }
+
+    /// Finds the cheapest way to edit `a` into `b`.
+    pub fn distance(&self, a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut dcol: Vec<usize> = (0..=b.len()).map(|j| j * self.insertion).collect();
+        for (i, sc) in a.chars().enumerate() {
+            let mut current = dcol[0];
+            dcol[0] = (i + 1) * self.deletion;
+            for (j, &tc) in b.iter().enumerate() {
+                let next = dcol[j + 1];
+                let substitution = if sc == tc {
+                    current
+                } else {
+                    current + self.substitution_cost(sc, tc)
+                };
+                dcol[j + 1] = cmp::min(
+                    substitution,
+                    cmp::min(next + self.deletion, dcol[j] + self.insertion),
+                );
+                current = next;
+            }
+        }
+        dcol[b.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lev_distance;
+
+    #[test]
+    fn test_weighted_levenshtein() {
+        let plain = WeightedLevenshtein::new();
+        for (a, b) in [("kitten", "sitting"), ("", "abc"), ("äbc", "ab")] {
+            assert_eq!(plain.distance(a, b), lev_distance(a, b));
+        }
+
+        let cheap_substitution = WeightedLevenshtein::new().insertion(3).deletion(3);
+        assert_eq!(cheap_substitution.distance("AB-1234", "AB-1284"), 1);
+        assert_eq!(cheap_substitution.distance("AB-1234", "AB-123"), 3);
+
+        let asymmetric = WeightedLevenshtein::new()
+            .insertion(1)
+            .deletion(5)
+            .substitution(10);
+        assert_eq!(asymmetric.distance("abc", "abcd"), 1);
+        assert_eq!(asymmetric.distance("abcd", "abc"), 5);
+        assert_eq!(asymmetric.distance("abc", "abd"), 6);
+    }
+
+    #[test]
+    fn test_keyboard() {
+        let typos = WeightedLevenshtein::new()
+            .insertion(2)
+            .deletion(2)
+            .substitution(2)
+            .keyboard(Keyboard::Qwerty, 1);
+        assert_eq!(typos.distance("hwllo", "hello"), 1);
+        assert_eq!(typos.distance("hallo", "hello"), 2);
+
+        let azerty = typos.keyboard(Keyboard::Azerty, 1);
+        assert_eq!(azerty.distance("hzllo", "hello"), 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! Splitting identifiers into words for the sorted-words tier.
+
+use std::iter;
+
+use crate::CaseFolding;
+
+/// Where identifiers split into words, for matching the same words in a
+/// different order.
+///
+/// Splits at underscores only by default, as in
+/// [`find_best_match_for_name`](crate::find_best_match_for_name).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WordSplit {
+    underscore: bool,
+    hyphen: bool,
+    whitespace: bool,
+    camel_case: bool,
+}
+
+impl Default for WordSplit {
+    fn default() -> Self {
+        WordSplit {
+            underscore: true,
+            hyphen: false,
+            whitespace: false,
+            camel_case: false,
+        }
+    }
+}
+
+impl WordSplit {
+    /// Splits at underscores only.
+    pub fn new() -> Self {
+        WordSplit::default()
+    }
+
+    /// Splits at underscores, hyphens, whitespace and camelCase boundaries.
+    pub fn all() -> Self {
+        WordSplit {
+            underscore: true,
+            hyphen: true,
+            whitespace: true,
+            camel_case: true,
+        }
+    }
+
+    /// Splits at `_`, as in `snake_case`.
+    pub fn underscore(mut self, yes: bool) -> Self {
+        self.underscore = yes;
+        self
+    }
+
+    /// Splits at `-`, as in `kebab-case` flags.
+    pub fn hyphen(mut self, yes: bool) -> Self {
+        self.hyphen = yes;
+        self
+    }
+
+    /// Splits at whitespace.
+    pub fn whitespace(mut self, yes: bool) -> Self {
+        self.whitespace = yes;
+        self
+    }
+
+    /// Splits before every uppercase letter starting a word, as in
+    /// `camelCase` or `HTTPServer`. Words are then compared ignoring case,
+    /// so that `aLongerName` has the same words as `name_a_longer`.
+    pub fn camel_case(mut self, yes: bool) -> Self {
+        self.camel_case = yes;
+        self
+    }
+
+    /// The words of `s`, in order.
+    pub fn words(self, s: &str) -> impl Iterator<Item = &str> {
+        s.split(move |c: char| {
+            (self.underscore && c == '_')
+                || (self.hyphen && c == '-')
+                || (self.whitespace && c.is_whitespace())
+        })
+        .flat_map(move |piece| {
+            let mut rest = Some(piece);
+            iter::from_fn(move || {
+                let s = rest?;
+                let end = if self.camel_case {
+                    camel_boundary(s)
+                } else {
+                    s.len()
+                };
+                let (word, tail) = s.split_at(end);
+                rest = (!tail.is_empty()).then_some(tail);
+                Some(word)
+            })
+        })
+    }
+
+    /// Whether words are compared ignoring case.
+    pub(crate) fn ignores_case(self) -> bool {
+        self.camel_case
+    }
+
+    /// Whether `a` and `b` consist of the same words in any order, without
+    /// allocating.
+    pub fn same_words(self, a: &str, b: &str) -> bool {
+        let eq = |x: &str, y: &str| {
+            if self.ignores_case() {
+                CaseFolding::Unicode.eq(x, y)
+            } else {
+                x == y
+            }
+        };
+        let count = |s: &str, word: &str| self.words(s).filter(|w| eq(w, word)).count();
+        self.words(a).count() == self.words(b).count()
+            && self.words(a).all(|word| count(a, word) == count(b, word))
+    }
+}
+
+/// The end of the first camelCase word of `s`.
+fn camel_boundary(s: &str) -> usize {
+    let mut chars = s.char_indices().peekable();
+    let mut prev = match chars.next() {
+        Some((_, c)) => c,
+        None => return 0,
+    };
+    while let Some((i, c)) = chars.next() {
+        let next_is_lower = chars.peek().is_some_and(|&(_, n)| n.is_lowercase());
+        if c.is_uppercase()
+            && (prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower))
+        {
+            return i;
+        }
+        prev = c;
+    }
+    s.len()
+}
+
+/// Converts an identifier in any naming convention to `snake_case`, so that
+/// `maxRetryCount`, `MaxRetryCount`, `max-retry-count` and `MAX_RETRY_COUNT`
+/// all become `max_retry_count`.
+///
+/// Words are split as by [`WordSplit::all`] and lowercased.
+pub fn to_snake_case(s: &str) -> String {
+    let mut snake = String::with_capacity(s.len() + 4);
+    for (i, word) in WordSplit::all().words(s).enumerate() {
+        if i > 0 {
+            snake.push('_');
+        }
+        snake.extend(word.chars().flat_map(char::to_lowercase));
+    }
+    snake
+}
+
+/// Finds a candidate consisting of the same words as `lookup` in a different
+/// order, the last one if several do.
+///
+/// This is the last tier of
+/// [`find_best_match_for_name`](crate::find_best_match_for_name), with
+/// configurable word splitting.
+pub fn find_match_by_sorted_words<T>(
+    iter_names: impl Iterator<Item = T>,
+    lookup: &str,
+    split: WordSplit,
+) -> Option<T>
+where
+    T: AsRef<str>,
+{
+    iter_names
+        .filter(|candidate| split.same_words(candidate.as_ref(), lookup))
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let words = |split: WordSplit, s| split.words(s).collect::<Vec<_>>();
+        assert_eq!(words(WordSplit::new(), "a_b-c d"), ["a", "b-c d"]);
+        assert_eq!(words(WordSplit::new(), "a__b"), ["a", "", "b"]);
+        assert_eq!(
+            words(WordSplit::new().hyphen(true).whitespace(true), "a_b-c d"),
+            ["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            words(WordSplit::all(), "parseHTTPServer2Config"),
+            ["parse", "HTTP", "Server2", "Config"]
+        );
+        assert_eq!(
+            words(WordSplit::all(), "aLongerName"),
+            ["a", "Longer", "Name"]
+        );
+        assert_eq!(words(WordSplit::all(), ""), [""]);
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        for s in [
+            "maxRetryCount",
+            "MaxRetryCount",
+            "max-retry-count",
+            "MAX_RETRY_COUNT",
+        ] {
+            assert_eq!(to_snake_case(s), "max_retry_count", "{:?}", s);
+        }
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("__privateField"), "__private_field");
+        assert_eq!(to_snake_case(""), "");
+    }
+
+    #[test]
+    fn test_find_match_by_sorted_words() {
+        let input = ["aLongerVariableName", "--dry-run", "variable_name"];
+        assert_eq!(
+            find_match_by_sorted_words(input.iter(), "a_variable_longer_name", WordSplit::new()),
+            None
+        );
+        assert_eq!(
+            find_match_by_sorted_words(input.iter(), "a_variable_longer_name", WordSplit::all()),
+            Some(&"aLongerVariableName")
+        );
+        assert_eq!(
+            find_match_by_sorted_words(input.iter(), "--run-dry", WordSplit::new().hyphen(true)),
+            Some(&"--dry-run")
+        );
+        assert_eq!(
+            find_match_by_sorted_words(input.iter(), "name_variable", WordSplit::new()),
+            Some(&"variable_name")
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Checks that `find_best_static_match` never touches the heap.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use lev_distance::find_best_static_match;
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+static COMMANDS: &[&str] = &[
+    "build", "check", "clean", "doc", "new", "init", "run", "test",
+];
+
+#[test]
+fn test_no_allocations() {
+    let before = ALLOCATIONS.with(Cell::get);
+    let results = [
+        find_best_static_match(COMMANDS, "buld", None),
+        find_best_static_match(COMMANDS, "TEST", None),
+        find_best_static_match(COMMANDS, "xyzzy", None),
+        find_best_static_match(COMMANDS, "chekc", Some(2)),
+    ];
+    let after = ALLOCATIONS.with(Cell::get);
+
+    assert_eq!(after - before, 0);
+    assert_eq!(results, [Some("build"), Some("test"), None, Some("check")]);
+}